@@ -6,6 +6,16 @@ pub const SESSION_SEED: &[u8] = b"session";
 pub const ATTENDANCE_SEED: &[u8] = b"attendance";
 pub const ENROLLMENT_SEED: &[u8] = b"enrollment";
 pub const WAITLIST_SEED: &[u8] = b"waitlist";
+pub const WAITLIST_QUEUE_SEED: &[u8] = b"waitlist_queue";
+pub const LOTTERY_SEED: &[u8] = b"lottery";
+pub const RENT_POOL_SEED: &[u8] = b"rent_pool";
+pub const CORRECTION_LOG_SEED: &[u8] = b"correction_log";
+pub const TRAINER_ATTENDANCE_SEED: &[u8] = b"trainer_attendance";
+pub const ROSTER_SEED: &[u8] = b"roster";
+pub const ACCUMULATOR_SEED: &[u8] = b"accumulator";
+pub const SESSION_SERIES_SEED: &[u8] = b"session_series";
+pub const TRAINER_SCHEDULE_SEED: &[u8] = b"trainer_schedule";
+pub const ROOM_SEED: &[u8] = b"room";
 
 // Tailles maximales des champs
 pub const MAX_TITLE_LENGTH: usize = 200;
@@ -13,6 +23,34 @@ pub const MAX_DESCRIPTION_LENGTH: usize = 1000;
 pub const MAX_MESSAGE_LENGTH: usize = 200;
 pub const MAX_LOCATION_LENGTH: usize = 50;
 pub const MAX_ROLE_LENGTH: usize = 10;
+pub const MAX_SEGMENT_NAME_LENGTH: usize = 32;
+pub const MAX_CORRECTION_REASON_LENGTH: usize = 100;
+pub const MAX_JUSTIFICATION_URI_LENGTH: usize = 200;
+
+// Demi-journées d'émargement par session
+pub const MAX_SESSION_SEGMENTS: usize = 4;
+
+// Séries de sessions récurrentes
+pub const MAX_SERIES_SESSIONS: u16 = 100;
+pub const MAX_EXCLUDED_DATES: usize = 16;
+pub const SECONDS_PER_DAY: i64 = 86400;
+
+// Sessions à venir indexées par formateur
+pub const MAX_SCHEDULE_ENTRIES: usize = 100;
+
+// Salles et équipements
+pub const MAX_ROOM_NAME_LENGTH: usize = 32; // utilisé comme seed
+pub const MAX_ROOM_BOOKINGS: usize = 100;
+// Réservations de salle suivies par formation pour plafonner les inscrits sur place
+pub const MAX_VENUE_BOOKINGS: usize = 32;
+pub const EQUIPMENT_PROJECTOR: u16 = 1 << 0;
+pub const EQUIPMENT_VIDEO_CONFERENCE: u16 = 1 << 1;
+pub const EQUIPMENT_COMPUTERS: u16 = 1 << 2;
+pub const EQUIPMENT_WHEELCHAIR_ACCESS: u16 = 1 << 3;
+
+// Contestations et justificatifs par présence ; chacun donne lieu au plus à une
+// entrée du journal de corrections de l'étudiant, qui ne peut donc pas déborder
+pub const MAX_CORRECTION_REQUESTS: usize = 3;
 
 // Espaces des comptes
 pub const FORMATION_SPACE: usize = 8 + // Discriminator
@@ -56,11 +94,35 @@ pub const MIN_SESSION_DURATION: i64 = 1800; // 30 minutes en secondes
 pub const MAX_SESSION_DURATION: i64 = 28800; // 8 heures en secondes
 pub const MIN_FORMATION_DURATION: i64 = 24 * 60 * 60; // 1 jour en secondes
 pub const MAX_FORMATION_DURATION: i64 = 365 * 24 * 60 * 60; // 1 an en secondes
+pub const MIN_PROMOTION_TIMEOUT: i64 = 60 * 60; // 1 heure en secondes
+pub const MAX_PROMOTION_TIMEOUT: i64 = 7 * 24 * 60 * 60; // 7 jours en secondes
+pub const CHECK_IN_OPEN_MARGIN: i64 = 15 * 60; // ouverture du pointage 15 minutes avant le début
+pub const DEFAULT_LATE_THRESHOLD: i64 = 10 * 60; // retard au-delà de 10 minutes après le début
+pub const EARLY_DEPARTURE_TOLERANCE: i64 = 10 * 60; // départ anticipé au-delà de 10 minutes avant la fin
+pub const CHECK_IN_CODE_WINDOW: i64 = 30; // durée de validité d'un code de pointage en secondes
+pub const MAX_CODE_CHAIN_LENGTH: u32 = 4096; // nombre maximal de fenêtres d'une chaîne de hachage
+pub const MAX_CODE_CHAIN_STEPS: i64 = 240; // hachages au plus par vérification de code (2 heures de fenêtres)
+
+// Préfixe des messages de code de pointage signés par le formateur
+pub const CHECK_IN_CODE_DOMAIN: &[u8] = b"alyrasign:check-in";
+pub const OFFLINE_CHECK_IN_DOMAIN: &[u8] = b"alyrasign:offline-check-in";
 
 // Contraintes de capacité
 pub const DEFAULT_WAITLIST_SIZE: u8 = 10;
 pub const MAX_WAITLIST_SIZE: u8 = 50;
 pub const MAX_FORMATION_CAPACITY: u8 = 100;
+pub const ROSTER_SIZE: usize = MAX_FORMATION_CAPACITY as usize;
+pub const ROSTER_BITMAP_LEN: usize = ROSTER_SIZE.div_ceil(8);
+
+// Profondeur de l'accumulateur de Merkle des présences (2^20 feuilles)
+pub const MERKLE_DEPTH: usize = 20;
+pub const MAX_LOTTERY_ENTRANTS: u8 = 200;
+// Le tirage utilise le hachage du premier slot produit `LOTTERY_SLOT_DELAY` slots
+// après la clôture des inscriptions ; le formateur dispose ensuite de
+// `LOTTERY_REVEAL_TIMEOUT` pour révéler son secret, faute de quoi un slot de repli
+// est fixé avec le même délai
+pub const LOTTERY_SLOT_DELAY: u64 = 32;
+pub const LOTTERY_REVEAL_TIMEOUT: i64 = 24 * 60 * 60; // 24 heures
 
 // Constantes pour les rôles
 pub const ROLE_STUDENT: &str = "student";
@@ -80,6 +142,7 @@ pub const TYPE_HYBRID: &str = "hybrid";
 pub const ATTENDANCE_PRESENT: &str = "present";
 pub const ATTENDANCE_ABSENT: &str = "absent";
 pub const ATTENDANCE_LATE: &str = "late";
+pub const ATTENDANCE_LEFT_EARLY: &str = "left_early";
 
 // Constantes pour les permissions
 pub const PERMISSION_ADMIN: &str = "admin";
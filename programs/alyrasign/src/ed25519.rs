@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+use crate::error::AlyraError;
+
+// Taille d'un bloc d'offsets dans les données d'une instruction Ed25519
+const OFFSETS_LEN: usize = 14;
+const OFFSETS_START: usize = 2;
+const PUBKEY_LEN: usize = 32;
+
+// Message dont la signature a été vérifiée par le programme natif Ed25519
+pub struct VerifiedMessage {
+    pub signer: Pubkey,
+    pub message: Vec<u8>,
+}
+
+// Collecte les signatures vérifiées par les instructions Ed25519 placées avant
+// l'instruction courante. Une signature invalide fait échouer toute la
+// transaction, il suffit donc de relire les clés et messages.
+pub fn verified_messages(instructions_sysvar: &AccountInfo) -> Result<Vec<VerifiedMessage>> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    let mut verified = Vec::new();
+
+    for index in 0..current_index {
+        let instruction = load_instruction_at_checked(index as usize, instructions_sysvar)?;
+        if instruction.program_id != ed25519_program::ID {
+            continue;
+        }
+
+        let data = &instruction.data;
+        require!(!data.is_empty(), AlyraError::InvalidEd25519Instruction);
+        let count = data[0] as usize;
+
+        for signature in 0..count {
+            let start = OFFSETS_START + signature * OFFSETS_LEN;
+            let offsets = data
+                .get(start..start + OFFSETS_LEN)
+                .ok_or(AlyraError::InvalidEd25519Instruction)?;
+            let read = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]);
+
+            // Les données signées doivent se trouver dans l'instruction Ed25519 elle-même
+            require!(
+                read(2) == u16::MAX && read(6) == u16::MAX && read(12) == u16::MAX,
+                AlyraError::InvalidEd25519Instruction
+            );
+
+            let pubkey_offset = read(4) as usize;
+            let message_offset = read(8) as usize;
+            let message_len = read(10) as usize;
+
+            let signer = data
+                .get(pubkey_offset..pubkey_offset + PUBKEY_LEN)
+                .ok_or(AlyraError::InvalidEd25519Instruction)?;
+            let message = data
+                .get(message_offset..message_offset + message_len)
+                .ok_or(AlyraError::InvalidEd25519Instruction)?;
+
+            verified.push(VerifiedMessage {
+                signer: Pubkey::try_from(signer).map_err(|_| AlyraError::InvalidEd25519Instruction)?,
+                message: message.to_vec(),
+            });
+        }
+    }
+
+    Ok(verified)
+}
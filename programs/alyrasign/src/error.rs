@@ -38,4 +38,130 @@ pub enum AlyraError {
     MessageTooLong,
     #[msg("Durée de session invalide")]
     InvalidSessionDuration,
+    #[msg("L'étudiant n'est pas en tête de la liste d'attente")]
+    NotFirstInLine,
+    #[msg("Délai de réponse à la promotion invalide")]
+    InvalidPromotionTimeout,
+    #[msg("L'offre de promotion n'a pas encore expiré")]
+    PromotionNotExpired,
+    #[msg("L'offre de promotion a expiré")]
+    PromotionExpired,
+    #[msg("Mode d'admission invalide pour cette opération")]
+    InvalidAdmissionMode,
+    #[msg("Les inscriptions au tirage au sort sont closes")]
+    LotteryRegistrationClosed,
+    #[msg("Les inscriptions au tirage au sort sont encore ouvertes")]
+    LotteryRegistrationOpen,
+    #[msg("L'étudiant est déjà inscrit au tirage au sort")]
+    AlreadyRegistered,
+    #[msg("Le nombre maximal de participants au tirage est atteint")]
+    LotteryFull,
+    #[msg("Le tirage au sort a déjà eu lieu")]
+    LotteryAlreadyDrawn,
+    #[msg("Le tirage au sort n'a pas encore eu lieu")]
+    LotteryNotDrawn,
+    #[msg("Le secret révélé ne correspond pas à l'engagement")]
+    InvalidLotteryReveal,
+    #[msg("L'étudiant n'a pas obtenu de place au tirage au sort")]
+    NotSelectedInLottery,
+    #[msg("L'étudiant ne participe pas au tirage au sort")]
+    NotLotteryEntrant,
+    #[msg("Le pointage n'est pas encore ouvert pour cette session")]
+    CheckInNotOpen,
+    #[msg("Le pointage est clos pour cette session")]
+    CheckInClosed,
+    #[msg("Seuil de retard invalide")]
+    InvalidLateThreshold,
+    #[msg("Instruction de vérification Ed25519 invalide")]
+    InvalidEd25519Instruction,
+    #[msg("Code de pointage invalide ou expiré")]
+    InvalidCheckInCode,
+    #[msg("Chaîne de codes de pointage invalide")]
+    InvalidCodeChain,
+    #[msg("Le pointage individuel n'est pas autorisé pour cette formation")]
+    SelfCheckInDisabled,
+    #[msg("L'appel par le formateur n'est pas autorisé pour cette formation")]
+    RollCallDisabled,
+    #[msg("Comptes de présence invalides")]
+    InvalidAttendanceAccounts,
+    #[msg("Statut de présence invalide pour cette opération")]
+    InvalidAttendanceStatus,
+    #[msg("Plages d'émargement invalides")]
+    InvalidSessionSegments,
+    #[msg("La session a déjà commencé")]
+    SessionAlreadyStarted,
+    #[msg("Présence déjà enregistrée")]
+    AlreadyCheckedIn,
+    #[msg("Le délai de confirmation des places tirées au sort est dépassé")]
+    LotteryClaimExpired,
+    #[msg("La session n'est pas encore terminée")]
+    SessionNotEnded,
+    #[msg("La feuille d'émargement de cette session est clôturée")]
+    SessionFinalized,
+    #[msg("La réserve de loyer de la formation est insuffisante")]
+    RentPoolInsufficient,
+    #[msg("Une contestation est déjà en cours pour cette présence")]
+    DisputeAlreadyOpen,
+    #[msg("Aucune contestation en cours pour cette présence")]
+    NoOpenDispute,
+    #[msg("Motif de correction trop long")]
+    ReasonTooLong,
+    #[msg("Journal des corrections plein")]
+    CorrectionLogFull,
+    #[msg("URI du justificatif trop longue")]
+    JustificationUriTooLong,
+    #[msg("Aucun justificatif en attente pour cette présence")]
+    NoPendingJustification,
+    #[msg("Le formateur n'a pas signé la feuille d'émargement")]
+    TrainerNotSigned,
+    #[msg("Mode de stockage des présences incompatible avec cette instruction")]
+    AttendanceStorageMismatch,
+    #[msg("Accumulateur de présences plein")]
+    AccumulatorFull,
+    #[msg("La session a été annulée")]
+    SessionCancelled,
+    #[msg("Règle de récurrence invalide")]
+    InvalidRecurrence,
+    #[msg("Comptes de session invalides")]
+    InvalidSessionAccounts,
+    #[msg("Le formateur a déjà une session sur ce créneau")]
+    ScheduleConflict,
+    #[msg("Planning du formateur plein")]
+    ScheduleFull,
+    #[msg("Nom de salle trop long")]
+    RoomNameTooLong,
+    #[msg("La salle est déjà réservée sur ce créneau")]
+    RoomConflict,
+    #[msg("Planning de la salle plein")]
+    RoomFull,
+    #[msg("La salle ne convient pas à cette session")]
+    RoomUnsuitable,
+    #[msg("Les sessions en ligne ne réservent pas de salle")]
+    RoomNotRequired,
+    #[msg("Réservation de salle incohérente avec la session")]
+    InvalidRoomBooking,
+    #[msg("Remplaçant invalide pour cette session")]
+    InvalidSubstitute,
+    #[msg("Modalité non proposée par cette formation")]
+    InvalidModality,
+    #[msg("Plus de place disponible pour cette modalité")]
+    ModalityFull,
+    #[msg("Le slot de référence du tirage n'est pas encore disponible")]
+    LotterySlotPending,
+    #[msg("Le hachage du slot de référence du tirage n'est plus disponible")]
+    LotterySlotHashUnavailable,
+    #[msg("Le formateur peut encore révéler le secret du tirage")]
+    LotteryRevealPending,
+    #[msg("Trop de fenêtres depuis le dernier code accepté : le formateur doit avancer la chaîne")]
+    CodeChainGapTooLarge,
+    #[msg("Des présences sont déjà enregistrées : le mode de stockage ne peut plus changer")]
+    AttendanceStorageLocked,
+    #[msg("Des inscrits n'ont pas encore de présence : lancer `mark_absent` avant la clôture")]
+    AbsencesNotMarked,
+    #[msg("Nombre maximal de contestations et justificatifs atteint pour cette présence")]
+    CorrectionLimitReached,
+    #[msg("Le délai de révélation du secret du tirage est dépassé")]
+    LotteryRevealExpired,
+    #[msg("Trop de salles réservées pour les sessions à venir de cette formation")]
+    VenueBookingsFull,
 } 
\ No newline at end of file
@@ -0,0 +1,225 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use crate::state::{Session, Formation, Enrollment, AttendanceAccumulator, ACCUMULATOR_SPACE};
+use crate::types::{AttendanceStatus, EnrollmentStatus};
+use crate::error::AlyraError;
+use crate::config::{ACCUMULATOR_SEED, MERKLE_DEPTH};
+use crate::instructions::record_attendance::verify_presence;
+use crate::merkle::{self, AttendanceLeaf};
+use crate::utils::read_account;
+
+#[derive(Accounts)]
+pub struct InitAttendanceAccumulator<'info> {
+    #[account(mut)]
+    pub trainer: Signer<'info>,
+    
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        init,
+        payer = trainer,
+        space = ACCUMULATOR_SPACE,
+        seeds = [ACCUMULATOR_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub accumulator: Account<'info, AttendanceAccumulator>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecordAccumulatedAttendance<'info> {
+    pub student: Signer<'info>,
+    
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+    
+    #[account(mut, address = session.formation)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        seeds = [b"enrollment", formation.key().as_ref(), student.key().as_ref()],
+        bump
+    )]
+    pub enrollment: Account<'info, Enrollment>,
+    
+    #[account(
+        mut,
+        seeds = [ACCUMULATOR_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub accumulator: Account<'info, AttendanceAccumulator>,
+    
+    /// CHECK: sysvar Instructions, lu pour retrouver la signature Ed25519 du code de pointage
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+// Crank sans permission, pendant du crank `mark_absent` : `remaining_accounts`
+// contient l'`Enrollment` de chaque étudiant, qui reçoit une feuille `Absent`
+// s'il n'en a pas pour la session. À appeler avant la clôture de la feuille
+// d'émargement.
+#[derive(Accounts)]
+pub struct MarkAbsentLeaves<'info> {
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+    
+    #[account(mut, address = session.formation)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        mut,
+        seeds = [ACCUMULATOR_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub accumulator: Account<'info, AttendanceAccumulator>,
+}
+
+pub fn init_attendance_accumulator(ctx: Context<InitAttendanceAccumulator>) -> Result<()> {
+    require!(
+        ctx.accounts.formation.trainer == ctx.accounts.trainer.key(),
+        AlyraError::UnauthorizedAccess
+    );
+    require!(
+        ctx.accounts.formation.uses_accumulator(),
+        AlyraError::AttendanceStorageMismatch
+    );
+
+    let accumulator = &mut ctx.accounts.accumulator;
+    accumulator.formation = ctx.accounts.formation.key();
+    accumulator.root = merkle::empty_root();
+    accumulator.leaf_count = 0;
+    accumulator.frontier = [[0u8; 32]; MERKLE_DEPTH];
+    Ok(())
+}
+
+// Aucun compte n'est créé : la présence n'existe qu'en feuille de l'accumulateur.
+// Une seule feuille par étudiant et par session, suivie par position dans la session.
+pub fn record_accumulated_attendance(
+    ctx: Context<RecordAccumulatedAttendance>,
+    code: Option<[u8; 32]>,
+) -> Result<()> {
+    let enrollment = &ctx.accounts.enrollment;
+    let formation = &ctx.accounts.formation;
+    require!(
+        enrollment.student == ctx.accounts.student.key()
+            && enrollment.status == EnrollmentStatus::Enrolled,
+        AlyraError::NotEnrolled
+    );
+    require!(
+        formation.uses_accumulator(),
+        AlyraError::AttendanceStorageMismatch
+    );
+    require!(
+        formation.allows_self_check_in(),
+        AlyraError::SelfCheckInDisabled
+    );
+    require!(
+        ctx.accounts.session.is_open_for_attendance(),
+        AlyraError::SessionFinalized
+    );
+    require!(
+        !ctx.accounts.session.is_recorded(enrollment),
+        AlyraError::AlreadyCheckedIn
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let status = ctx.accounts.session.attendance_status_at(now, formation.late_threshold)?;
+
+    verify_presence(&mut ctx.accounts.session, &ctx.accounts.instructions, code, now)?;
+
+    let leaf = AttendanceLeaf {
+        formation: formation.key(),
+        session: ctx.accounts.session.key(),
+        student: enrollment.student,
+        status,
+        modality: enrollment.modality,
+        check_in_time: now,
+    };
+    let leaf_hash = leaf.hash()?;
+    let accumulator = &mut ctx.accounts.accumulator;
+    let index = accumulator.leaf_count;
+    accumulator.append(leaf_hash)?;
+    ctx.accounts.session.add_leaf(enrollment, status, &leaf_hash)?;
+
+    emit!(AttendanceLeafAppendedEvent {
+        leaf,
+        index,
+        root: accumulator.root,
+    });
+
+    ctx.accounts.formation.lock_attendance_storage();
+    Ok(())
+}
+
+pub fn mark_absent_leaves<'info>(ctx: Context<'_, '_, '_, 'info, MarkAbsentLeaves<'info>>) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let formation = &ctx.accounts.formation;
+    let accumulator = &mut ctx.accounts.accumulator;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        now > session.check_in_closes_at(),
+        AlyraError::SessionNotEnded
+    );
+    require!(
+        formation.uses_accumulator(),
+        AlyraError::AttendanceStorageMismatch
+    );
+    require!(
+        session.is_open_for_attendance(),
+        AlyraError::SessionFinalized
+    );
+
+    let formation_key = formation.key();
+    let session_key = session.key();
+    for enrollment_info in ctx.remaining_accounts.iter() {
+        let enrollment: Enrollment = read_account(enrollment_info)?;
+        require!(
+            enrollment.formation == formation_key
+                && enrollment.status == EnrollmentStatus::Enrolled,
+            AlyraError::NotEnrolled
+        );
+
+        // Étudiant déjà pointé ou déjà marqué : rien à faire
+        if session.is_recorded(&enrollment) {
+            continue;
+        }
+        // Inscrit après la fin de la session : il n'y est pas attendu
+        if enrollment.created_at > session.end_time {
+            session.mark_recorded(&enrollment);
+            continue;
+        }
+
+        let leaf = AttendanceLeaf {
+            formation: formation_key,
+            session: session_key,
+            student: enrollment.student,
+            status: AttendanceStatus::Absent,
+            modality: enrollment.modality,
+            check_in_time: 0,
+        };
+        let leaf_hash = leaf.hash()?;
+        let index = accumulator.leaf_count;
+        accumulator.append(leaf_hash)?;
+        session.add_leaf(&enrollment, AttendanceStatus::Absent, &leaf_hash)?;
+
+        emit!(AttendanceLeafAppendedEvent {
+            leaf,
+            index,
+            root: accumulator.root,
+        });
+    }
+
+    session.updated_at = now;
+
+    ctx.accounts.formation.lock_attendance_storage();
+    Ok(())
+}
+
+#[event]
+pub struct AttendanceLeafAppendedEvent {
+    pub leaf: AttendanceLeaf,
+    pub index: u64,
+    pub root: [u8; 32],
+}
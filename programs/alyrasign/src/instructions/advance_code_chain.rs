@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::state::Session;
+use crate::types::CheckInMethod;
+use crate::error::AlyraError;
+
+#[derive(Accounts)]
+pub struct AdvanceCodeChain<'info> {
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+    
+    pub trainer: Signer<'info>,
+}
+
+// Publie le maillon d'une fenêtre passée pour rapprocher le point de départ de
+// la vérification, lorsque personne n'a pointé depuis longtemps. Le maillon
+// d'une fenêtre passée ne permet plus de pointer.
+pub fn advance_code_chain(ctx: Context<AdvanceCodeChain>, link: [u8; 32], window: i64) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        session.is_acting_trainer(&ctx.accounts.trainer.key()),
+        AlyraError::UnauthorizedAccess
+    );
+    require!(
+        session.check_in_method == CheckInMethod::HashChain,
+        AlyraError::InvalidCodeChain
+    );
+    require!(
+        session.is_open_for_attendance(),
+        AlyraError::SessionFinalized
+    );
+    require!(
+        window < session.code_window_at(now),
+        AlyraError::InvalidCheckInCode
+    );
+
+    session.accept_chain_link(link, window)?;
+    session.updated_at = now;
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use crate::state::{Session, Formation, TrainerSchedule, ScheduleEntry, TRAINER_SCHEDULE_SPACE};
+use crate::error::AlyraError;
+use crate::config::{STORAGE_SEED, TRAINER_SCHEDULE_SEED};
+use crate::ProgramStorage;
+
+#[derive(Accounts)]
+#[instruction(substitute: Pubkey)]
+pub struct AssignSubstitute<'info> {
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+    
+    #[account(address = session.formation)]
+    pub formation: Account<'info, Formation>,
+    
+    // Le créneau est ajouté au planning du remplaçant, avec contrôle des conflits
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = TRAINER_SCHEDULE_SPACE,
+        seeds = [TRAINER_SCHEDULE_SEED, substitute.as_ref()],
+        bump
+    )]
+    pub substitute_schedule: Account<'info, TrainerSchedule>,
+    
+    // Planning du remplaçant précédent, à fournir en cas de changement de remplaçant
+    #[account(
+        mut,
+        seeds = [TRAINER_SCHEDULE_SEED, session.substitute.as_ref()],
+        bump
+    )]
+    pub previous_substitute_schedule: Option<Account<'info, TrainerSchedule>>,
+    
+    #[account(
+        seeds = [STORAGE_SEED],
+        bump
+    )]
+    pub storage: Account<'info, ProgramStorage>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn assign_substitute(ctx: Context<AssignSubstitute>, substitute: Pubkey) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == ctx.accounts.formation.trainer || authority == ctx.accounts.storage.admin,
+        AlyraError::UnauthorizedAccess
+    );
+
+    let session = &mut ctx.accounts.session;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        session.is_open_for_attendance() && now <= session.end_time,
+        AlyraError::SessionFinalized
+    );
+    require!(
+        substitute != Pubkey::default()
+            && substitute != session.trainer
+            && substitute != session.substitute,
+        AlyraError::InvalidSubstitute
+    );
+
+    let previous_substitute = session.substitute;
+    if previous_substitute != Pubkey::default() {
+        let previous_schedule = ctx.accounts.previous_substitute_schedule
+            .as_mut()
+            .ok_or(AlyraError::InvalidSubstitute)?;
+        previous_schedule.remove(&session.key());
+    }
+
+    let substitute_schedule = &mut ctx.accounts.substitute_schedule;
+    substitute_schedule.trainer = substitute;
+    substitute_schedule.insert(ScheduleEntry {
+        session: session.key(),
+        formation: session.formation,
+        start_time: session.start_time,
+        end_time: session.end_time,
+    }, now)?;
+
+    session.substitute = substitute;
+    session.substitute_assigned_by = authority;
+    session.substitute_assigned_at = now;
+    session.updated_at = now;
+
+    // Trace pour la paie et l'audit
+    emit!(SubstituteAssignedEvent {
+        session: session.key(),
+        formation: session.formation,
+        trainer: session.trainer,
+        substitute,
+        previous_substitute,
+        assigned_by: authority,
+        start_time: session.start_time,
+        end_time: session.end_time,
+        assigned_at: now,
+    });
+    Ok(())
+}
+
+#[event]
+pub struct SubstituteAssignedEvent {
+    pub session: Pubkey,
+    pub formation: Pubkey,
+    pub trainer: Pubkey,
+    pub substitute: Pubkey,
+    pub previous_substitute: Pubkey,
+    pub assigned_by: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub assigned_at: i64,
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use crate::state::{Session, Formation, TrainerSchedule, Room};
+use crate::types::SessionStatus;
+use crate::error::AlyraError;
+use crate::config::{MAX_MESSAGE_LENGTH, TRAINER_SCHEDULE_SEED};
+
+#[derive(Accounts)]
+pub struct CancelSession<'info> {
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+    
+    #[account(
+        mut,
+        seeds = [TRAINER_SCHEDULE_SEED, trainer.key().as_ref()],
+        bump
+    )]
+    pub trainer_schedule: Account<'info, TrainerSchedule>,
+    
+    // Salle réservée par la session, obligatoire si `session.room` est renseigné
+    #[account(mut, address = session.room @ AlyraError::InvalidRoomBooking)]
+    pub room: Option<Account<'info, Room>>,
+    
+    // Formation de la session, obligatoire avec la salle pour suivre ses réservations
+    #[account(mut, address = session.formation)]
+    pub formation: Option<Account<'info, Formation>>,
+    
+    // Planning du remplaçant, obligatoire si `session.substitute` est renseigné
+    #[account(
+        mut,
+        seeds = [TRAINER_SCHEDULE_SEED, session.substitute.as_ref()],
+        bump
+    )]
+    pub substitute_schedule: Option<Account<'info, TrainerSchedule>>,
+    
+    pub trainer: Signer<'info>,
+}
+
+pub fn cancel_session(ctx: Context<CancelSession>, reason: String) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        session.trainer == ctx.accounts.trainer.key(),
+        AlyraError::UnauthorizedAccess
+    );
+    require!(
+        reason.len() <= MAX_MESSAGE_LENGTH,
+        AlyraError::ReasonTooLong
+    );
+    require!(
+        session.status != SessionStatus::Cancelled,
+        AlyraError::SessionCancelled
+    );
+    require!(
+        session.is_open_for_attendance(),
+        AlyraError::SessionFinalized
+    );
+
+    session.status = SessionStatus::Cancelled;
+    session.updated_at = now;
+
+    // Le créneau libéré redevient disponible pour le formateur
+    ctx.accounts.trainer_schedule.remove(&session.key());
+    if session.substitute != Pubkey::default() {
+        let substitute_schedule = ctx.accounts.substitute_schedule
+            .as_mut()
+            .ok_or(AlyraError::InvalidSubstitute)?;
+        substitute_schedule.remove(&session.key());
+    }
+    if session.room != Pubkey::default() {
+        let room = ctx.accounts.room.as_mut().ok_or(AlyraError::InvalidRoomBooking)?;
+        room.release(&session.key());
+        room.updated_at = now;
+        let formation = ctx.accounts.formation.as_mut().ok_or(AlyraError::InvalidRoomBooking)?;
+        formation.remove_venue_booking(&session.key());
+        formation.updated_at = now;
+        session.room = Pubkey::default();
+    }
+
+    emit!(SessionCancelledEvent {
+        session: session.key(),
+        formation: session.formation,
+        start_time: session.start_time,
+        reason,
+    });
+    Ok(())
+}
+
+#[event]
+pub struct SessionCancelledEvent {
+    pub session: Pubkey,
+    pub formation: Pubkey,
+    pub start_time: i64,
+    pub reason: String,
+}
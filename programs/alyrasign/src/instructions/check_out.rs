@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::state::{Session, Attendance};
+use crate::error::AlyraError;
+
+#[derive(Accounts)]
+pub struct CheckOut<'info> {
+    pub student: Signer<'info>,
+    
+    pub session: Account<'info, Session>,
+    
+    #[account(
+        mut,
+        seeds = [b"attendance", session.key().as_ref(), student.key().as_ref()],
+        bump
+    )]
+    pub attendance: Account<'info, Attendance>,
+}
+
+pub fn check_out(ctx: Context<CheckOut>) -> Result<()> {
+    let session = &ctx.accounts.session;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        session.is_open_for_attendance(),
+        AlyraError::SessionFinalized
+    );
+    require!(
+        now <= session.check_in_closes_at(),
+        AlyraError::CheckInClosed
+    );
+
+    ctx.accounts.attendance.check_out(session, now)
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::state::Formation;
+use crate::types::{AttendanceMode, AttendanceStorage};
+use crate::error::AlyraError;
+use crate::config::MAX_SESSION_DURATION;
+
+#[derive(Accounts)]
+pub struct ConfigureAttendance<'info> {
+    #[account(mut)]
+    pub formation: Account<'info, Formation>,
+    
+    pub trainer: Signer<'info>,
+}
+
+pub fn configure_attendance(
+    ctx: Context<ConfigureAttendance>,
+    late_threshold: i64,
+    attendance_mode: AttendanceMode,
+    attendance_storage: AttendanceStorage,
+) -> Result<()> {
+    let formation = &mut ctx.accounts.formation;
+    require!(
+        formation.trainer == ctx.accounts.trainer.key(),
+        AlyraError::UnauthorizedAccess
+    );
+    require!(
+        (0..=MAX_SESSION_DURATION).contains(&late_threshold),
+        AlyraError::InvalidLateThreshold
+    );
+
+    // Changer de stockage laisserait les présences existantes dans l'ancien
+    require!(
+        attendance_storage == formation.attendance_storage || !formation.attendance_recorded,
+        AlyraError::AttendanceStorageLocked
+    );
+
+    formation.late_threshold = late_threshold;
+    formation.attendance_mode = attendance_mode;
+    formation.attendance_storage = attendance_storage;
+    formation.updated_at = Clock::get()?.unix_timestamp;
+    Ok(())
+}
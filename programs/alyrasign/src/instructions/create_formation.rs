@@ -1,8 +1,8 @@
 use anchor_lang::prelude::*;
-use crate::state::Formation;
-use crate::types::FormationType;
+use crate::state::{Formation, WaitlistQueue, VenueBooking, WAITLIST_QUEUE_SPACE};
+use crate::types::{FormationType, AttendanceMode, AttendanceStorage};
 use crate::error::AlyraError;
-use crate::config::{MAX_TITLE_LENGTH, MAX_DESCRIPTION_LENGTH, MAX_FORMATION_CAPACITY, MAX_WAITLIST_SIZE};
+use crate::config::{MAX_TITLE_LENGTH, MAX_DESCRIPTION_LENGTH, MAX_FORMATION_CAPACITY, MAX_WAITLIST_SIZE, WAITLIST_QUEUE_SEED, MIN_PROMOTION_TIMEOUT, MAX_PROMOTION_TIMEOUT, DEFAULT_LATE_THRESHOLD, ROSTER_BITMAP_LEN, ROSTER_SIZE, MAX_VENUE_BOOKINGS};
 
 #[derive(Accounts)]
 pub struct CreateFormation<'info> {
@@ -18,6 +18,15 @@ pub struct CreateFormation<'info> {
     )]
     pub formation: Account<'info, Formation>,
     
+    #[account(
+        init,
+        payer = trainer,
+        space = WAITLIST_QUEUE_SPACE,
+        seeds = [WAITLIST_QUEUE_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub waitlist_queue: Account<'info, WaitlistQueue>,
+    
     pub system_program: Program<'info, System>,
 }
 
@@ -28,6 +37,7 @@ pub fn create_formation(
     formation_type: FormationType,
     max_students: u8,
     waitlist_size: u8,
+    promotion_timeout: i64,
 ) -> Result<()> {
     require!(
         title.len() <= MAX_TITLE_LENGTH,
@@ -45,6 +55,10 @@ pub fn create_formation(
         waitlist_size <= MAX_WAITLIST_SIZE,
         AlyraError::WaitlistFull
     );
+    require!(
+        (MIN_PROMOTION_TIMEOUT..=MAX_PROMOTION_TIMEOUT).contains(&promotion_timeout),
+        AlyraError::InvalidPromotionTimeout
+    );
 
     let formation = &mut ctx.accounts.formation;
     formation.trainer = ctx.accounts.trainer.key();
@@ -55,7 +69,26 @@ pub fn create_formation(
     formation.waitlist_size = waitlist_size;
     formation.current_students = 0;
     formation.current_waitlisted = 0;
+    formation.pending_promotions = 0;
+    formation.promotion_timeout = promotion_timeout;
+    formation.late_threshold = DEFAULT_LATE_THRESHOLD;
+    formation.attendance_mode = AttendanceMode::SelfCheckIn;
+    formation.attendance_storage = AttendanceStorage::Accounts;
+    formation.attendance_recorded = false;
+    formation.occupied_positions = [0u8; ROSTER_BITMAP_LEN];
+    formation.position_generations = [0u32; ROSTER_SIZE];
+    formation.session_count = 0;
+    // Par défaut chaque modalité peut accueillir toute la promotion
+    formation.onsite_capacity = max_students;
+    formation.remote_capacity = max_students;
+    formation.current_onsite = 0;
+    formation.current_remote = 0;
+    formation.venue_bookings = [VenueBooking::default(); MAX_VENUE_BOOKINGS];
     formation.created_at = Clock::get()?.unix_timestamp;
     formation.updated_at = Clock::get()?.unix_timestamp;
+
+    let waitlist_queue = &mut ctx.accounts.waitlist_queue;
+    waitlist_queue.formation = formation.key();
+    waitlist_queue.slots = Vec::new();
     Ok(())
 } 
\ No newline at end of file
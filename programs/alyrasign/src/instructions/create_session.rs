@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
-use crate::state::{Session, Formation};
+use crate::state::{Session, Formation, TrainerSchedule, ScheduleEntry, SESSION_SPACE, SESSION_SEED, TRAINER_SCHEDULE_SPACE};
+use crate::types::{CheckInMethod, HashChainCommitment, SegmentRule, SessionStatus};
 use crate::error::AlyraError;
-use crate::config::{MAX_TITLE_LENGTH, MAX_DESCRIPTION_LENGTH, MIN_SESSION_DURATION, MAX_SESSION_DURATION};
+use crate::config::{MAX_TITLE_LENGTH, MAX_DESCRIPTION_LENGTH, MIN_SESSION_DURATION, MAX_SESSION_DURATION, MAX_CODE_CHAIN_LENGTH, TRAINER_SCHEDULE_SEED};
 
 #[derive(Accounts)]
 pub struct CreateSession<'info> {
@@ -14,12 +15,21 @@ pub struct CreateSession<'info> {
     #[account(
         init,
         payer = trainer,
-        space = 8 + std::mem::size_of::<Session>(),
-        seeds = [b"session", formation.key().as_ref()],
+        space = SESSION_SPACE,
+        seeds = [SESSION_SEED, formation.key().as_ref(), &formation.session_count.to_le_bytes()],
         bump
     )]
     pub session: Account<'info, Session>,
     
+    #[account(
+        init_if_needed,
+        payer = trainer,
+        space = TRAINER_SCHEDULE_SPACE,
+        seeds = [TRAINER_SCHEDULE_SEED, trainer.key().as_ref()],
+        bump
+    )]
+    pub trainer_schedule: Account<'info, TrainerSchedule>,
+    
     pub system_program: Program<'info, System>,
 }
 
@@ -29,6 +39,7 @@ pub fn create_session(
     description: String,
     start_time: i64,
     end_time: i64,
+    hash_chain: Option<HashChainCommitment>,
 ) -> Result<()> {
     require!(
         title.len() <= MAX_TITLE_LENGTH,
@@ -56,7 +67,44 @@ pub fn create_session(
     session.description = description;
     session.start_time = start_time;
     session.end_time = end_time;
+    session.last_code_window = 0;
+    // Sans chaîne de hachage, les codes de pointage sont signés par le formateur
+    match hash_chain {
+        Some(chain) => {
+            require!(
+                chain.length > 0 && chain.length <= MAX_CODE_CHAIN_LENGTH,
+                AlyraError::InvalidCodeChain
+            );
+            session.check_in_method = CheckInMethod::HashChain;
+            session.code_anchor = chain.tail;
+            session.code_anchor_window = -1;
+            session.code_chain_length = chain.length;
+        }
+        None => {
+            session.check_in_method = CheckInMethod::TrainerSignature;
+        }
+    }
+    session.segments = Vec::new();
+    session.segment_rule = SegmentRule::AllSegments;
+    session.status = SessionStatus::Scheduled;
+    session.attendance_count = 0;
+    session.finalized_count = 0;
+    session.trainer_signed = false;
+    session.attendance_hash = [0u8; 32];
     session.created_at = Clock::get()?.unix_timestamp;
     session.updated_at = Clock::get()?.unix_timestamp;
+
+    let trainer_schedule = &mut ctx.accounts.trainer_schedule;
+    trainer_schedule.trainer = ctx.accounts.trainer.key();
+    trainer_schedule.insert(ScheduleEntry {
+        session: session.key(),
+        formation: session.formation,
+        start_time,
+        end_time,
+    }, session.created_at)?;
+
+    let formation = &mut ctx.accounts.formation;
+    formation.session_count = formation.session_count.checked_add(1)
+        .ok_or(AlyraError::Overflow)?;
     Ok(())
 } 
\ No newline at end of file
@@ -0,0 +1,178 @@
+use anchor_lang::prelude::*;
+use crate::state::{Session, Formation, Attendance, CorrectionLog, CorrectionEntry, CORRECTION_LOG_SPACE};
+use crate::types::{AttendanceStatus, DisputeStatus};
+use crate::error::AlyraError;
+use crate::config::{ATTENDANCE_SEED, CORRECTION_LOG_SEED, STORAGE_SEED};
+use crate::ProgramStorage;
+
+#[derive(Accounts)]
+pub struct OpenDispute<'info> {
+    pub session: Account<'info, Session>,
+    
+    #[account(
+        mut,
+        seeds = [ATTENDANCE_SEED, session.key().as_ref(), student.key().as_ref()],
+        bump
+    )]
+    pub attendance: Account<'info, Attendance>,
+    
+    pub student: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+    
+    #[account(address = session.formation)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        mut,
+        seeds = [ATTENDANCE_SEED, session.key().as_ref(), attendance.student.as_ref()],
+        bump
+    )]
+    pub attendance: Account<'info, Attendance>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = CORRECTION_LOG_SPACE,
+        seeds = [CORRECTION_LOG_SEED, session.key().as_ref(), attendance.student.as_ref()],
+        bump
+    )]
+    pub correction_log: Account<'info, CorrectionLog>,
+    
+    #[account(
+        seeds = [STORAGE_SEED],
+        bump
+    )]
+    pub storage: Account<'info, ProgramStorage>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+// Possible y compris après la clôture de la session : c'est la seule voie
+// de modification d'une présence figée, et elle est tracée dans le journal
+pub fn open_dispute(ctx: Context<OpenDispute>, evidence_hash: [u8; 32]) -> Result<()> {
+    let attendance = &mut ctx.accounts.attendance;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        attendance.status != AttendanceStatus::Present,
+        AlyraError::InvalidAttendanceStatus
+    );
+    require!(
+        attendance.dispute_status != DisputeStatus::Open,
+        AlyraError::DisputeAlreadyOpen
+    );
+    // Une contestation rejetée peut être rouverte, dans la limite commune aux justificatifs
+    attendance.request_correction()?;
+
+    attendance.dispute_status = DisputeStatus::Open;
+    attendance.evidence_hash = evidence_hash;
+    attendance.disputed_at = now;
+    attendance.updated_at = now;
+
+    emit!(DisputeOpenedEvent {
+        session: attendance.session,
+        student: attendance.student,
+        status: attendance.status,
+        evidence_hash,
+    });
+    Ok(())
+}
+
+pub fn resolve_dispute(
+    ctx: Context<ResolveDispute>,
+    new_status: AttendanceStatus,
+    reason: String,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == ctx.accounts.formation.trainer || authority == ctx.accounts.storage.admin,
+        AlyraError::UnauthorizedAccess
+    );
+
+    let session = &mut ctx.accounts.session;
+    let attendance = &mut ctx.accounts.attendance;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        attendance.dispute_status == DisputeStatus::Open,
+        AlyraError::NoOpenDispute
+    );
+    // Une absence n'est excusée que sur justificatif
+    require!(
+        new_status != AttendanceStatus::Excused,
+        AlyraError::InvalidAttendanceStatus
+    );
+
+    let old_status = attendance.status;
+    let counted = session.is_finalized(attendance);
+    if counted {
+        session.tally(attendance, false)?;
+    }
+
+    // La correction vaut pour toutes les plages d'émargement de la session
+    attendance.status = new_status;
+    for check_in in attendance.segment_check_ins[..session.segments.len()].iter_mut() {
+        if new_status == AttendanceStatus::Absent {
+            *check_in = 0;
+        } else if *check_in == 0 {
+            *check_in = now;
+        }
+    }
+    attendance.dispute_status = if new_status == old_status {
+        DisputeStatus::Rejected
+    } else {
+        DisputeStatus::Accepted
+    };
+    attendance.updated_at = now;
+
+    if counted {
+        session.tally(attendance, true)?;
+        session.updated_at = now;
+    }
+
+    let correction_log = &mut ctx.accounts.correction_log;
+    correction_log.session = session.key();
+    correction_log.student = attendance.student;
+    correction_log.append(CorrectionEntry {
+        student: attendance.student,
+        old_status,
+        new_status,
+        actor: authority,
+        reason,
+        timestamp: now,
+    })?;
+
+    emit!(AttendanceCorrectedEvent {
+        session: session.key(),
+        student: attendance.student,
+        old_status,
+        new_status,
+        actor: authority,
+    });
+    Ok(())
+}
+
+#[event]
+pub struct DisputeOpenedEvent {
+    pub session: Pubkey,
+    pub student: Pubkey,
+    pub status: AttendanceStatus,
+    pub evidence_hash: [u8; 32],
+}
+
+#[event]
+pub struct AttendanceCorrectedEvent {
+    pub session: Pubkey,
+    pub student: Pubkey,
+    pub old_status: AttendanceStatus,
+    pub new_status: AttendanceStatus,
+    pub actor: Pubkey,
+}
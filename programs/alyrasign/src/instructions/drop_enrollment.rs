@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::state::{Formation, Enrollment, WaitlistEntry, WaitlistQueue};
+use crate::types::EnrollmentStatus;
+use crate::error::AlyraError;
+use crate::config::WAITLIST_QUEUE_SEED;
+use crate::instructions::waitlist::offer_seat;
+
+#[derive(Accounts)]
+pub struct DropEnrollment<'info> {
+    #[account(mut)]
+    pub formation: Account<'info, Formation>,
+    
+    // Fermée : l'étudiant peut de nouveau rejoindre la liste d'attente ou s'inscrire,
+    // l'inscription étant recréée à la même adresse
+    #[account(
+        mut,
+        close = student,
+        seeds = [b"enrollment", formation.key().as_ref(), student.key().as_ref()],
+        bump
+    )]
+    pub enrollment: Account<'info, Enrollment>,
+    
+    #[account(
+        mut,
+        seeds = [WAITLIST_QUEUE_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub waitlist_queue: Account<'info, WaitlistQueue>,
+    
+    // Entrée en tête de file : si fournie, la place libérée lui est proposée
+    // dans la même transaction, sinon le crank `offer_promotion` s'en charge
+    #[account(mut)]
+    pub next_entry: Option<Account<'info, WaitlistEntry>>,
+    
+    #[account(mut)]
+    pub student: Signer<'info>,
+}
+
+pub fn drop_enrollment(ctx: Context<DropEnrollment>) -> Result<()> {
+    let formation = &mut ctx.accounts.formation;
+    let enrollment = &ctx.accounts.enrollment;
+
+    require!(
+        enrollment.status == EnrollmentStatus::Enrolled,
+        AlyraError::InvalidEnrollmentStatus
+    );
+
+    formation.current_students = formation.current_students.checked_sub(1)
+        .ok_or(AlyraError::Underflow)?;
+    formation.free_position(enrollment.position);
+    formation.remove_from_modality(enrollment.modality)?;
+    formation.updated_at = Clock::get()?.unix_timestamp;
+
+    if let Some(next_entry) = ctx.accounts.next_entry.as_mut() {
+        offer_seat(formation, &mut ctx.accounts.waitlist_queue, next_entry)?;
+    }
+    Ok(())
+}
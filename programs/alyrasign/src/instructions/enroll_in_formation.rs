@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
-use crate::state::{Formation, Enrollment};
-use crate::types::EnrollmentStatus;
+use crate::state::{Formation, Enrollment, Lottery};
+use crate::types::{EnrollmentStatus, AdmissionMode, Modality};
 use crate::error::AlyraError;
+use crate::config::LOTTERY_SEED;
 
 #[derive(Accounts)]
 pub struct EnrollInFormation<'info> {
@@ -20,26 +21,64 @@ pub struct EnrollInFormation<'info> {
     )]
     pub enrollment: Account<'info, Enrollment>,
     
+    // Requis lorsque la formation est en mode tirage au sort
+    #[account(
+        mut,
+        seeds = [LOTTERY_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub lottery: Option<Account<'info, Lottery>>,
+    
     pub system_program: Program<'info, System>,
 }
 
-pub fn enroll_in_formation(ctx: Context<EnrollInFormation>) -> Result<()> {
+pub fn enroll_in_formation(ctx: Context<EnrollInFormation>, modality: Modality) -> Result<()> {
     let enrollment = &mut ctx.accounts.enrollment;
     let formation = &mut ctx.accounts.formation;
 
-    require!(
-        formation.current_students < formation.max_students,
-        AlyraError::FormationFull
-    );
+    let student = ctx.accounts.student.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    // Les gagnants du tirage confirment leur place réservée, dans le délai imparti
+    let mut claims_lottery_seat = false;
+    let mut refusal = AlyraError::FormationFull;
+    if formation.admission_mode == AdmissionMode::Lottery {
+        let lottery = ctx.accounts.lottery.as_mut().ok_or(AlyraError::InvalidAdmissionMode)?;
+        require!(lottery.drawn, AlyraError::LotteryNotDrawn);
+        let winner = matches!(lottery.rank_of(&student), Some(rank) if rank < lottery.seats as usize);
+        if winner && !lottery.seats_released && now <= lottery.drawn_at + formation.promotion_timeout {
+            let index = lottery.entrant_index(&student).ok_or(AlyraError::NotLotteryEntrant)?;
+            lottery.mark_claimed(index)?;
+            lottery.updated_at = now;
+            claims_lottery_seat = true;
+        } else if winner {
+            refusal = AlyraError::LotteryClaimExpired;
+        } else if lottery.entrant_index(&student).is_some() {
+            refusal = AlyraError::NotSelectedInLottery;
+        }
+    }
+
+    // Places non pourvues par le tirage (ou libérées depuis) : premier arrivé,
+    // les étudiants en liste d'attente restant prioritaires
+    let open_to_all = formation.has_available_slots() && formation.waiting_count() == 0;
+    if !claims_lottery_seat && !open_to_all {
+        return Err(refusal.into());
+    }
 
     enrollment.formation = formation.key();
-    enrollment.student = ctx.accounts.student.key();
+    enrollment.student = student;
     enrollment.status = EnrollmentStatus::Enrolled;
-    enrollment.position = formation.current_students;
+    (enrollment.position, enrollment.position_generation) = formation.assign_position()?;
+    enrollment.modality = modality;
+    formation.add_to_modality(modality, now)?;
     enrollment.created_at = Clock::get()?.unix_timestamp;
     enrollment.updated_at = Clock::get()?.unix_timestamp;
 
-    formation.current_students = formation.current_students.checked_add(1).unwrap();
+    if claims_lottery_seat {
+        formation.claim_reserved_seat()?;
+    } else {
+        formation.current_students = formation.current_students.checked_add(1).unwrap();
+    }
     formation.updated_at = Clock::get()?.unix_timestamp;
     Ok(())
 } 
\ No newline at end of file
@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use crate::state::{Session, Formation, Attendance};
+use crate::types::SessionStatus;
+use crate::error::AlyraError;
+use crate::utils::read_account;
+
+// Clôture de la feuille d'émargement : `remaining_accounts` contient les comptes
+// Attendance de la session triés par étudiant. Si tous ne tiennent pas dans une
+// transaction, l'appel est répété avec la suite jusqu'à `attendance_count`.
+// Avec l'accumulateur, aucun compte n'est à fournir : les totaux et l'empreinte
+// sont tenus à chaque feuille ajoutée.
+#[derive(Accounts)]
+pub struct FinalizeSession<'info> {
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+    
+    #[account(address = session.formation)]
+    pub formation: Account<'info, Formation>,
+    
+    pub trainer: Signer<'info>,
+}
+
+pub fn finalize_session<'info>(
+    ctx: Context<'_, '_, '_, 'info, FinalizeSession<'info>>,
+) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        session.is_acting_trainer(&ctx.accounts.trainer.key()),
+        AlyraError::UnauthorizedAccess
+    );
+    // Le pointage en retard reste possible jusqu'à `check_in_closes_at`, après
+    // quoi le crank `mark_absent` (ou `mark_absent_leaves`) complète la feuille
+    require!(
+        now > session.check_in_closes_at(),
+        AlyraError::SessionNotEnded
+    );
+    require!(
+        session.status != SessionStatus::Cancelled,
+        AlyraError::SessionCancelled
+    );
+    require!(
+        session.status != SessionStatus::Finalized,
+        AlyraError::SessionFinalized
+    );
+    require!(
+        session.trainer_signed,
+        AlyraError::TrainerNotSigned
+    );
+
+    require!(
+        session.status != SessionStatus::Scheduled || session.covers_enrolled(&ctx.accounts.formation),
+        AlyraError::AbsencesNotMarked
+    );
+
+    // Dès le premier lot, plus aucune présence ne peut être créée ni modifiée
+    session.status = SessionStatus::Finalizing;
+
+    let session_key = session.key();
+    for attendance_info in ctx.remaining_accounts.iter() {
+        let attendance: Attendance = read_account(attendance_info)?;
+        require_keys_eq!(attendance.session, session_key, AlyraError::InvalidAttendanceAccounts);
+        session.finalize_attendance(&attendance)?;
+    }
+
+    if session.finalized_count == session.attendance_count {
+        session.status = SessionStatus::Finalized;
+        session.finalized_at = now;
+
+        emit!(SessionFinalizedEvent {
+            session: session_key,
+            present_count: session.present_count,
+            late_count: session.late_count,
+            absent_count: session.absent_count,
+            left_early_count: session.left_early_count,
+            excused_count: session.excused_count,
+            attendance_rate: session.attendance_rate(),
+            attendance_hash: session.attendance_hash,
+        });
+    }
+    session.updated_at = now;
+    Ok(())
+}
+
+#[event]
+pub struct SessionFinalizedEvent {
+    pub session: Pubkey,
+    pub present_count: u32,
+    pub late_count: u32,
+    pub absent_count: u32,
+    pub left_early_count: u32,
+    pub excused_count: u32,
+    // Points de base, absences excusées exclues
+    pub attendance_rate: u16,
+    pub attendance_hash: [u8; 32],
+}
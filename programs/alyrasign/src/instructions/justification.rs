@@ -0,0 +1,163 @@
+use anchor_lang::prelude::*;
+use crate::state::{Session, Attendance, CorrectionLog, CorrectionEntry, CORRECTION_LOG_SPACE};
+use crate::types::{AttendanceStatus, JustificationStatus};
+use crate::error::AlyraError;
+use crate::config::{ATTENDANCE_SEED, CORRECTION_LOG_SEED, STORAGE_SEED, MAX_JUSTIFICATION_URI_LENGTH};
+use crate::ProgramStorage;
+
+#[derive(Accounts)]
+pub struct SubmitJustification<'info> {
+    pub session: Account<'info, Session>,
+    
+    #[account(
+        mut,
+        seeds = [ATTENDANCE_SEED, session.key().as_ref(), student.key().as_ref()],
+        bump
+    )]
+    pub attendance: Account<'info, Attendance>,
+    
+    pub student: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReviewJustification<'info> {
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+    
+    #[account(
+        mut,
+        seeds = [ATTENDANCE_SEED, session.key().as_ref(), attendance.student.as_ref()],
+        bump
+    )]
+    pub attendance: Account<'info, Attendance>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = CORRECTION_LOG_SPACE,
+        seeds = [CORRECTION_LOG_SEED, session.key().as_ref(), attendance.student.as_ref()],
+        bump
+    )]
+    pub correction_log: Account<'info, CorrectionLog>,
+    
+    #[account(
+        seeds = [STORAGE_SEED],
+        bump
+    )]
+    pub storage: Account<'info, ProgramStorage>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+// Le document reste hors chaîne : seule son empreinte engage l'étudiant
+pub fn submit_justification(
+    ctx: Context<SubmitJustification>,
+    justification_hash: [u8; 32],
+    justification_uri: String,
+) -> Result<()> {
+    let attendance = &mut ctx.accounts.attendance;
+
+    require!(
+        justification_uri.len() <= MAX_JUSTIFICATION_URI_LENGTH,
+        AlyraError::JustificationUriTooLong
+    );
+    require!(
+        attendance.status == AttendanceStatus::Absent,
+        AlyraError::InvalidAttendanceStatus
+    );
+    require!(
+        attendance.justification_status != JustificationStatus::Pending,
+        AlyraError::InvalidAttendanceStatus
+    );
+    attendance.request_correction()?;
+
+    attendance.justification_hash = justification_hash;
+    attendance.justification_uri = justification_uri;
+    attendance.justification_status = JustificationStatus::Pending;
+    attendance.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(JustificationSubmittedEvent {
+        session: attendance.session,
+        student: attendance.student,
+        justification_hash,
+    });
+    Ok(())
+}
+
+pub fn review_justification(
+    ctx: Context<ReviewJustification>,
+    accept: bool,
+    reason: String,
+) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let attendance = &mut ctx.accounts.attendance;
+    let now = Clock::get()?.unix_timestamp;
+
+    let authority = ctx.accounts.authority.key();
+    require!(
+        session.is_acting_trainer(&authority) || authority == ctx.accounts.storage.admin,
+        AlyraError::UnauthorizedAccess
+    );
+    require!(
+        attendance.justification_status == JustificationStatus::Pending,
+        AlyraError::NoPendingJustification
+    );
+
+    let old_status = attendance.status;
+    if accept {
+        // Une contestation a pu corriger l'absence depuis le dépôt du justificatif
+        require!(
+            attendance.status == AttendanceStatus::Absent,
+            AlyraError::InvalidAttendanceStatus
+        );
+        let counted = session.is_finalized(attendance);
+        if counted {
+            session.tally(attendance, false)?;
+        }
+        attendance.status = AttendanceStatus::Excused;
+        if counted {
+            session.tally(attendance, true)?;
+            session.updated_at = now;
+        }
+        attendance.justification_status = JustificationStatus::Accepted;
+    } else {
+        attendance.justification_status = JustificationStatus::Rejected;
+    }
+    attendance.updated_at = now;
+
+    let correction_log = &mut ctx.accounts.correction_log;
+    correction_log.session = session.key();
+    correction_log.student = attendance.student;
+    correction_log.append(CorrectionEntry {
+        student: attendance.student,
+        old_status,
+        new_status: attendance.status,
+        actor: authority,
+        reason,
+        timestamp: now,
+    })?;
+
+    emit!(JustificationReviewedEvent {
+        session: session.key(),
+        student: attendance.student,
+        accepted: accept,
+    });
+    Ok(())
+}
+
+#[event]
+pub struct JustificationSubmittedEvent {
+    pub session: Pubkey,
+    pub student: Pubkey,
+    pub justification_hash: [u8; 32],
+}
+
+#[event]
+pub struct JustificationReviewedEvent {
+    pub session: Pubkey,
+    pub student: Pubkey,
+    pub accepted: bool,
+}
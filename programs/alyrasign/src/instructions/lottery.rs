@@ -0,0 +1,388 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::sysvar::slot_hashes;
+use anchor_lang::solana_program::slot_hashes::MAX_ENTRIES as SLOT_HASHES_ENTRIES;
+use crate::state::{Formation, Lottery, LOTTERY_SPACE};
+use crate::types::AdmissionMode;
+use crate::error::AlyraError;
+use crate::config::{LOTTERY_SEED, MAX_LOTTERY_ENTRANTS, LOTTERY_SLOT_DELAY, LOTTERY_REVEAL_TIMEOUT};
+
+#[derive(Accounts)]
+pub struct OpenLottery<'info> {
+    #[account(mut)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        init,
+        payer = trainer,
+        space = LOTTERY_SPACE,
+        seeds = [LOTTERY_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub lottery: Account<'info, Lottery>,
+    
+    #[account(mut)]
+    pub trainer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterForLottery<'info> {
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        mut,
+        seeds = [LOTTERY_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub lottery: Account<'info, Lottery>,
+    
+    pub student: Signer<'info>,
+}
+
+// Cranks sans permission : fixent le slot de référence à la clôture des
+// inscriptions, puis relèvent son hachage
+#[derive(Accounts)]
+pub struct CloseLotteryRegistration<'info> {
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        mut,
+        seeds = [LOTTERY_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub lottery: Account<'info, Lottery>,
+}
+
+#[derive(Accounts)]
+pub struct RecordLotterySlotHash<'info> {
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        mut,
+        seeds = [LOTTERY_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub lottery: Account<'info, Lottery>,
+    
+    /// CHECK: sysvar SlotHashes, lu directement car trop volumineux pour être désérialisé
+    #[account(address = slot_hashes::ID)]
+    pub recent_slothashes: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DrawLottery<'info> {
+    #[account(mut)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        mut,
+        seeds = [LOTTERY_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub lottery: Account<'info, Lottery>,
+    
+    pub trainer: Signer<'info>,
+}
+
+// Cranks sans permission, une fois le délai de révélation dépassé : fixent un
+// slot de repli, puis tirent au sort avec son hachage
+#[derive(Accounts)]
+pub struct PinLotteryFallbackSlot<'info> {
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        mut,
+        seeds = [LOTTERY_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub lottery: Account<'info, Lottery>,
+}
+
+#[derive(Accounts)]
+pub struct ForceDrawLottery<'info> {
+    #[account(mut)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        mut,
+        seeds = [LOTTERY_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub lottery: Account<'info, Lottery>,
+    
+    /// CHECK: sysvar SlotHashes, lu directement car trop volumineux pour être désérialisé
+    #[account(address = slot_hashes::ID)]
+    pub recent_slothashes: UncheckedAccount<'info>,
+}
+
+// Crank sans permission : rend à la liste d'attente les places tirées au sort non confirmées
+#[derive(Accounts)]
+pub struct ReleaseLotterySeats<'info> {
+    #[account(mut)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        mut,
+        seeds = [LOTTERY_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub lottery: Account<'info, Lottery>,
+}
+
+pub fn open_lottery(
+    ctx: Context<OpenLottery>,
+    registration_end: i64,
+    commitment: [u8; 32],
+) -> Result<()> {
+    let formation = &mut ctx.accounts.formation;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        formation.trainer == ctx.accounts.trainer.key(),
+        AlyraError::UnauthorizedAccess
+    );
+    require!(
+        formation.current_students == 0 && formation.current_waitlisted == 0,
+        AlyraError::InvalidAdmissionMode
+    );
+    require!(
+        registration_end > now,
+        AlyraError::InvalidTimeRange
+    );
+
+    let lottery = &mut ctx.accounts.lottery;
+    lottery.formation = formation.key();
+    lottery.commitment = commitment;
+    lottery.registration_end = registration_end;
+    lottery.entrants = Vec::new();
+    lottery.ranking = Vec::new();
+    lottery.target_slot = 0;
+    lottery.reveal_deadline = 0;
+    lottery.fallback_slot = 0;
+    lottery.drawn = false;
+    lottery.created_at = now;
+    lottery.updated_at = now;
+
+    formation.admission_mode = AdmissionMode::Lottery;
+    formation.updated_at = now;
+    Ok(())
+}
+
+pub fn register_for_lottery(ctx: Context<RegisterForLottery>) -> Result<()> {
+    let lottery = &mut ctx.accounts.lottery;
+    let student = ctx.accounts.student.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        now <= lottery.registration_end,
+        AlyraError::LotteryRegistrationClosed
+    );
+    require!(
+        lottery.entrant_index(&student).is_none(),
+        AlyraError::AlreadyRegistered
+    );
+    require!(
+        lottery.entrants.len() < MAX_LOTTERY_ENTRANTS as usize,
+        AlyraError::LotteryFull
+    );
+
+    lottery.entrants.push(student);
+    lottery.updated_at = now;
+
+    emit!(LotteryRegisteredEvent {
+        formation: lottery.formation,
+        student,
+        index: (lottery.entrants.len() - 1) as u8,
+    });
+    Ok(())
+}
+
+// Fixe le slot de référence après la clôture des inscriptions. Si son hachage
+// n'a pas été relevé avant de sortir du sysvar SlotHashes, un nouveau slot est fixé.
+pub fn close_lottery_registration(ctx: Context<CloseLotteryRegistration>) -> Result<()> {
+    let lottery = &mut ctx.accounts.lottery;
+    let clock = Clock::get()?;
+
+    require!(
+        clock.unix_timestamp > lottery.registration_end,
+        AlyraError::LotteryRegistrationOpen
+    );
+    require!(
+        lottery.target_slot == 0
+            || (lottery.reveal_deadline == 0
+                && clock.slot > lottery.target_slot + SLOT_HASHES_ENTRIES as u64),
+        AlyraError::LotterySlotPending
+    );
+
+    lottery.target_slot = clock.slot + LOTTERY_SLOT_DELAY;
+    lottery.updated_at = clock.unix_timestamp;
+    Ok(())
+}
+
+pub fn record_lottery_slot_hash(ctx: Context<RecordLotterySlotHash>) -> Result<()> {
+    let lottery = &mut ctx.accounts.lottery;
+    let clock = Clock::get()?;
+
+    require!(
+        lottery.target_slot != 0 && lottery.reveal_deadline == 0 && clock.slot > lottery.target_slot,
+        AlyraError::LotterySlotPending
+    );
+
+    let (slot, slot_hash) = {
+        let data = ctx.accounts.recent_slothashes.try_borrow_data()?;
+        Lottery::find_slot_hash(&data, lottery.target_slot)
+            .ok_or(AlyraError::LotterySlotHashUnavailable)?
+    };
+
+    lottery.slot = slot;
+    lottery.slot_hash = slot_hash;
+    lottery.reveal_deadline = clock.unix_timestamp + LOTTERY_REVEAL_TIMEOUT;
+    lottery.updated_at = clock.unix_timestamp;
+    Ok(())
+}
+
+pub fn draw_lottery(ctx: Context<DrawLottery>, secret: [u8; 32]) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        ctx.accounts.formation.trainer == ctx.accounts.trainer.key(),
+        AlyraError::UnauthorizedAccess
+    );
+    require!(
+        hashv(&[&secret]).to_bytes() == ctx.accounts.lottery.commitment,
+        AlyraError::InvalidLotteryReveal
+    );
+    // Passé le délai, seul le tirage de repli reste possible
+    require!(
+        now <= ctx.accounts.lottery.reveal_deadline,
+        AlyraError::LotteryRevealExpired
+    );
+
+    apply_draw(&mut ctx.accounts.formation, &mut ctx.accounts.lottery, secret, true, now)
+}
+
+// Le formateur n'a pas révélé son secret à temps. Le hachage du slot de référence
+// ne suffit pas : le formateur le connaît, et pourrait comparer les deux tirages
+// avant de choisir de révéler ou non. Un slot postérieur au délai est donc fixé,
+// inconnu de tous au moment où la révélation devient impossible. Comme à la
+// clôture des inscriptions, un nouveau slot est fixé si son hachage n'a pas été
+// relevé à temps.
+pub fn pin_lottery_fallback_slot(ctx: Context<PinLotteryFallbackSlot>) -> Result<()> {
+    let lottery = &mut ctx.accounts.lottery;
+    let clock = Clock::get()?;
+
+    require!(!lottery.drawn, AlyraError::LotteryAlreadyDrawn);
+    require!(
+        lottery.reveal_deadline != 0 && clock.unix_timestamp > lottery.reveal_deadline,
+        AlyraError::LotteryRevealPending
+    );
+    require!(
+        lottery.fallback_slot == 0
+            || clock.slot > lottery.fallback_slot + SLOT_HASHES_ENTRIES as u64,
+        AlyraError::LotterySlotPending
+    );
+
+    lottery.fallback_slot = clock.slot + LOTTERY_SLOT_DELAY;
+    lottery.updated_at = clock.unix_timestamp;
+    Ok(())
+}
+
+pub fn force_draw_lottery(ctx: Context<ForceDrawLottery>) -> Result<()> {
+    let clock = Clock::get()?;
+    let lottery = &mut ctx.accounts.lottery;
+
+    require!(
+        lottery.fallback_slot != 0 && clock.slot > lottery.fallback_slot,
+        AlyraError::LotterySlotPending
+    );
+
+    let (slot, fallback_hash) = {
+        let data = ctx.accounts.recent_slothashes.try_borrow_data()?;
+        Lottery::find_slot_hash(&data, lottery.fallback_slot)
+            .ok_or(AlyraError::LotterySlotHashUnavailable)?
+    };
+    lottery.fallback_slot = slot;
+
+    apply_draw(&mut ctx.accounts.formation, lottery, fallback_hash, false, clock.unix_timestamp)
+}
+
+fn apply_draw(
+    formation: &mut Account<Formation>,
+    lottery: &mut Account<Lottery>,
+    secret: [u8; 32],
+    revealed: bool,
+    now: i64,
+) -> Result<()> {
+    require!(!lottery.drawn, AlyraError::LotteryAlreadyDrawn);
+    require!(lottery.reveal_deadline != 0, AlyraError::LotterySlotPending);
+
+    let seed = Lottery::compute_seed(&secret, &lottery.slot_hash, &formation.key());
+    lottery.ranking = Lottery::compute_ranking(&seed, lottery.entrants.len());
+    lottery.seats = formation.open_seats().min(lottery.entrants.len() as u8);
+    lottery.secret = secret;
+    lottery.revealed = revealed;
+    lottery.seed = seed;
+    lottery.drawn = true;
+    lottery.drawn_at = now;
+    lottery.updated_at = now;
+
+    // Les places gagnées restent réservées jusqu'à leur confirmation
+    formation.pending_promotions = formation.pending_promotions.checked_add(lottery.seats)
+        .ok_or(AlyraError::Overflow)?;
+    formation.updated_at = now;
+
+    emit!(LotteryDrawnEvent {
+        formation: formation.key(),
+        secret,
+        revealed,
+        slot: lottery.slot,
+        slot_hash: lottery.slot_hash,
+        seed,
+        entrants: lottery.entrants.len() as u8,
+        seats: lottery.seats,
+    });
+    Ok(())
+}
+
+pub fn release_lottery_seats(ctx: Context<ReleaseLotterySeats>) -> Result<()> {
+    let formation = &mut ctx.accounts.formation;
+    let lottery = &mut ctx.accounts.lottery;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(lottery.drawn, AlyraError::LotteryNotDrawn);
+    require!(
+        !lottery.seats_released && now > lottery.drawn_at + formation.promotion_timeout,
+        AlyraError::PromotionNotExpired
+    );
+
+    let unclaimed = lottery.seats.checked_sub(lottery.claimed_count)
+        .ok_or(AlyraError::Underflow)?;
+    formation.pending_promotions = formation.pending_promotions.checked_sub(unclaimed)
+        .ok_or(AlyraError::Underflow)?;
+    formation.updated_at = now;
+
+    lottery.seats_released = true;
+    lottery.updated_at = now;
+    Ok(())
+}
+
+#[event]
+pub struct LotteryRegisteredEvent {
+    pub formation: Pubkey,
+    pub student: Pubkey,
+    pub index: u8,
+}
+
+#[event]
+pub struct LotteryDrawnEvent {
+    pub formation: Pubkey,
+    pub secret: [u8; 32],
+    pub revealed: bool,
+    pub slot: u64,
+    pub slot_hash: [u8; 32],
+    pub seed: [u8; 32],
+    pub entrants: u8,
+    pub seats: u8,
+}
@@ -0,0 +1,145 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{Session, Formation, Enrollment, Attendance, ATTENDANCE_SPACE};
+use crate::types::{AttendanceStatus, EnrollmentStatus};
+use crate::error::AlyraError;
+use crate::config::{ATTENDANCE_SEED, RENT_POOL_SEED};
+use crate::utils::{create_pda_account, read_account, write_account};
+
+// Réserve de lamports propre à chaque formation (compte système sans données),
+// qui paie le loyer des présences créées par le crank `mark_absent`
+#[derive(Accounts)]
+pub struct FundRentPool<'info> {
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        mut,
+        seeds = [RENT_POOL_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub rent_pool: SystemAccount<'info>,
+    
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+// Crank sans permission : `remaining_accounts` contient, pour chaque étudiant,
+// son `Enrollment` puis son compte `Attendance`, créé avec le statut `Absent`
+// s'il n'existe pas. À appeler avant la clôture de la feuille d'émargement.
+#[derive(Accounts)]
+pub struct MarkAbsent<'info> {
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+    
+    #[account(mut, address = session.formation)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        mut,
+        seeds = [RENT_POOL_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub rent_pool: SystemAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn fund_rent_pool(ctx: Context<FundRentPool>, amount: u64) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.funder.to_account_info(),
+                to: ctx.accounts.rent_pool.to_account_info(),
+            },
+        ),
+        amount,
+    )
+}
+
+pub fn mark_absent<'info>(ctx: Context<'_, '_, '_, 'info, MarkAbsent<'info>>) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let formation = &ctx.accounts.formation;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        now > session.check_in_closes_at(),
+        AlyraError::SessionNotEnded
+    );
+    require!(
+        formation.uses_attendance_accounts(),
+        AlyraError::AttendanceStorageMismatch
+    );
+    require!(
+        session.is_open_for_attendance(),
+        AlyraError::SessionFinalized
+    );
+    require!(
+        ctx.remaining_accounts.chunks_exact(2).remainder().is_empty(),
+        AlyraError::InvalidAttendanceAccounts
+    );
+
+    let space = ATTENDANCE_SPACE;
+    let rent = Rent::get()?;
+    let formation_key = formation.key();
+    let pool_bump = ctx.bumps.rent_pool;
+    let pool_seeds: &[&[u8]] = &[RENT_POOL_SEED, formation_key.as_ref(), &[pool_bump]];
+
+    let session_key = session.key();
+    for accounts in ctx.remaining_accounts.chunks(2) {
+        let (enrollment_info, attendance_info) = (&accounts[0], &accounts[1]);
+
+        let enrollment: Enrollment = read_account(enrollment_info)?;
+        require!(
+            enrollment.formation == formation_key
+                && enrollment.status == EnrollmentStatus::Enrolled,
+            AlyraError::NotEnrolled
+        );
+
+        let (expected, bump) = Pubkey::find_program_address(
+            &[ATTENDANCE_SEED, session_key.as_ref(), enrollment.student.as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(attendance_info.key(), expected, AlyraError::InvalidAttendanceAccounts);
+
+        // Étudiant déjà pointé ou déjà marqué : rien à faire
+        if !attendance_info.data_is_empty() {
+            continue;
+        }
+        // Inscrit après la fin de la session : il n'y est pas attendu
+        if enrollment.created_at > session.end_time {
+            session.mark_recorded(&enrollment);
+            continue;
+        }
+
+        // La réserve doit elle-même rester exemptée de loyer
+        require!(
+            ctx.accounts.rent_pool.lamports()
+                >= rent.minimum_balance(space) + rent.minimum_balance(0),
+            AlyraError::RentPoolInsufficient
+        );
+
+        create_pda_account(
+            &ctx.accounts.rent_pool.to_account_info(),
+            attendance_info,
+            &ctx.accounts.system_program.to_account_info(),
+            space,
+            &[
+                &[ATTENDANCE_SEED, session_key.as_ref(), enrollment.student.as_ref(), &[bump]],
+                pool_seeds,
+            ],
+        )?;
+
+        let attendance = Attendance::new(&enrollment, session_key, AttendanceStatus::Absent, 0, now);
+        write_account(attendance_info, &attendance)?;
+
+        session.add_attendance(&enrollment)?;
+    }
+
+    session.updated_at = now;
+
+    ctx.accounts.formation.lock_attendance_storage();
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use crate::state::{Session, Formation, Enrollment, Attendance, ATTENDANCE_SPACE};
+use crate::types::{AttendanceStatus, EnrollmentStatus};
+use crate::error::AlyraError;
+use crate::config::ATTENDANCE_SEED;
+use crate::utils::{create_pda_account, read_account, write_account};
+
+// Appel par le formateur : `remaining_accounts` contient, pour chaque étudiant,
+// son `Enrollment` puis son compte `Attendance` (créé s'il n'existe pas encore)
+#[derive(Accounts)]
+pub struct MarkAttendanceBatch<'info> {
+    #[account(mut)]
+    pub trainer: Signer<'info>,
+    
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+    
+    #[account(mut, address = session.formation)]
+    pub formation: Account<'info, Formation>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn mark_attendance_batch<'info>(
+    ctx: Context<'_, '_, '_, 'info, MarkAttendanceBatch<'info>>,
+    statuses: Vec<AttendanceStatus>,
+) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let formation = &ctx.accounts.formation;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        session.is_acting_trainer(&ctx.accounts.trainer.key()),
+        AlyraError::UnauthorizedAccess
+    );
+    require!(
+        formation.allows_roll_call(),
+        AlyraError::RollCallDisabled
+    );
+    require!(
+        formation.uses_attendance_accounts(),
+        AlyraError::AttendanceStorageMismatch
+    );
+    require!(
+        session.is_open_for_attendance(),
+        AlyraError::SessionFinalized
+    );
+    require!(
+        now >= session.check_in_opens_at() && now <= session.check_in_closes_at(),
+        AlyraError::CheckInClosed
+    );
+    require!(
+        ctx.remaining_accounts.len() == statuses.len() * 2,
+        AlyraError::InvalidAttendanceAccounts
+    );
+    // Une absence n'est excusée que sur justificatif
+    require!(
+        !statuses.contains(&AttendanceStatus::Excused),
+        AlyraError::InvalidAttendanceStatus
+    );
+
+    let session_key = session.key();
+    // Session découpée : l'appel vaut pour la plage en cours, comme le pointage
+    // individuel, sans quoi la présence ne serait signée pour aucune plage
+    let segment = if session.segments.is_empty() {
+        None
+    } else {
+        Some(session.segment_at(now).ok_or(AlyraError::CheckInNotOpen)?)
+    };
+    for (accounts, status) in ctx.remaining_accounts.chunks(2).zip(statuses) {
+        let (enrollment_info, attendance_info) = (&accounts[0], &accounts[1]);
+
+        let enrollment: Enrollment = read_account(enrollment_info)?;
+        require!(
+            enrollment.formation == formation.key()
+                && enrollment.status == EnrollmentStatus::Enrolled,
+            AlyraError::NotEnrolled
+        );
+
+        let (expected, bump) = Pubkey::find_program_address(
+            &[ATTENDANCE_SEED, session_key.as_ref(), enrollment.student.as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(attendance_info.key(), expected, AlyraError::InvalidAttendanceAccounts);
+
+        let check_in_time = if status == AttendanceStatus::Absent { 0 } else { now };
+        let segment_check_in = |attendance: &mut Attendance| {
+            if let Some(index) = segment {
+                attendance.segment_check_ins[index] = check_in_time;
+            }
+        };
+
+        if attendance_info.data_is_empty() {
+            create_pda_account(
+                &ctx.accounts.trainer.to_account_info(),
+                attendance_info,
+                &ctx.accounts.system_program.to_account_info(),
+                ATTENDANCE_SPACE,
+                &[&[ATTENDANCE_SEED, session_key.as_ref(), enrollment.student.as_ref(), &[bump]]],
+            )?;
+
+            let mut attendance = Attendance::new(&enrollment, session_key, status, check_in_time, now);
+            segment_check_in(&mut attendance);
+            write_account(attendance_info, &attendance)?;
+
+            session.add_attendance(&enrollment)?;
+        } else {
+            // L'appel du formateur prévaut sur un pointage individuel
+            let mut attendance: Attendance = read_account(attendance_info)?;
+            attendance.status = status;
+            attendance.check_in_time = check_in_time;
+            attendance.updated_at = now;
+            segment_check_in(&mut attendance);
+            write_account(attendance_info, &attendance)?;
+        }
+    }
+
+    ctx.accounts.formation.lock_attendance_storage();
+    Ok(())
+}
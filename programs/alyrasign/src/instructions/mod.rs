@@ -5,6 +5,27 @@ pub mod create_session;
 pub mod enroll_in_formation;
 pub mod record_attendance;
 pub mod promote_from_waitlist;
+pub mod drop_enrollment;
+pub mod lottery;
+pub mod configure_attendance;
+pub mod mark_attendance_batch;
+pub mod check_out;
+pub mod set_session_segments;
+pub mod finalize_session;
+pub mod mark_absent;
+pub mod dispute;
+pub mod justification;
+pub mod sign_trainer_attendance;
+pub mod submit_offline_attendance;
+pub mod roster;
+pub mod accumulator;
+pub mod reschedule_session;
+pub mod cancel_session;
+pub mod session_series;
+pub mod room;
+pub mod assign_substitute;
+pub mod modality;
+pub mod advance_code_chain;
 
 pub use waitlist::*;
 pub use request_access::*;
@@ -12,4 +33,25 @@ pub use create_formation::*;
 pub use create_session::*;
 pub use enroll_in_formation::*;
 pub use record_attendance::*;
-pub use promote_from_waitlist::*; 
\ No newline at end of file
+pub use promote_from_waitlist::*;
+pub use drop_enrollment::*;
+pub use lottery::*;
+pub use configure_attendance::*;
+pub use mark_attendance_batch::*;
+pub use check_out::*;
+pub use set_session_segments::*;
+pub use finalize_session::*;
+pub use mark_absent::*;
+pub use dispute::*;
+pub use justification::*;
+pub use sign_trainer_attendance::*;
+pub use submit_offline_attendance::*;
+pub use roster::*;
+pub use accumulator::*;
+pub use reschedule_session::*;
+pub use cancel_session::*;
+pub use session_series::*;
+pub use room::*;
+pub use assign_substitute::*;
+pub use modality::*;
+pub use advance_code_chain::*;
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use crate::state::{Formation, Enrollment};
+use crate::types::{EnrollmentStatus, FormationType, Modality};
+use crate::error::AlyraError;
+use crate::config::ENROLLMENT_SEED;
+
+#[derive(Accounts)]
+pub struct ConfigureHybridCapacity<'info> {
+    #[account(mut)]
+    pub formation: Account<'info, Formation>,
+
+    pub trainer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SwitchModality<'info> {
+    #[account(mut)]
+    pub formation: Account<'info, Formation>,
+
+    #[account(
+        mut,
+        seeds = [ENROLLMENT_SEED, formation.key().as_ref(), student.key().as_ref()],
+        bump
+    )]
+    pub enrollment: Account<'info, Enrollment>,
+
+    pub student: Signer<'info>,
+}
+
+// Répartition des places d'une formation hybride : la somme peut dépasser
+// `max_students`, qui reste le plafond global des inscriptions. Les places sur
+// place ne dépassent pas la plus petite salle réservée pour une session à venir.
+pub fn configure_hybrid_capacity(
+    ctx: Context<ConfigureHybridCapacity>,
+    onsite_capacity: u8,
+    remote_capacity: u8,
+) -> Result<()> {
+    let formation = &mut ctx.accounts.formation;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        formation.trainer == ctx.accounts.trainer.key(),
+        AlyraError::UnauthorizedAccess
+    );
+    require!(
+        formation.formation_type == FormationType::Hybrid,
+        AlyraError::InvalidModality
+    );
+    require!(
+        (formation.current_onsite..=formation.max_students.min(formation.venue_capacity(now))).contains(&onsite_capacity)
+            && (formation.current_remote..=formation.max_students).contains(&remote_capacity),
+        AlyraError::InvalidCapacity
+    );
+
+    formation.onsite_capacity = onsite_capacity;
+    formation.remote_capacity = remote_capacity;
+    formation.updated_at = now;
+    Ok(())
+}
+
+// Changement de modalité à la demande de l'étudiant, sous réserve de place
+pub fn switch_modality(ctx: Context<SwitchModality>, modality: Modality) -> Result<()> {
+    let formation = &mut ctx.accounts.formation;
+    let enrollment = &mut ctx.accounts.enrollment;
+
+    require!(
+        enrollment.status == EnrollmentStatus::Enrolled,
+        AlyraError::InvalidEnrollmentStatus
+    );
+    require!(
+        enrollment.modality != modality,
+        AlyraError::InvalidModality
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    formation.add_to_modality(modality, now)?;
+    formation.remove_from_modality(enrollment.modality)?;
+
+    enrollment.modality = modality;
+    enrollment.updated_at = now;
+    formation.updated_at = now;
+
+    emit!(ModalitySwitchedEvent {
+        formation: formation.key(),
+        student: enrollment.student,
+        modality,
+    });
+    Ok(())
+}
+
+#[event]
+pub struct ModalitySwitchedEvent {
+    pub formation: Pubkey,
+    pub student: Pubkey,
+    pub modality: Modality,
+}
@@ -1,7 +1,6 @@
 use anchor_lang::prelude::*;
 use crate::state::{Formation, WaitlistEntry, Enrollment};
-use crate::types::{WaitlistStatus, EnrollmentStatus};
-use crate::error::AlyraError;
+use crate::types::{EnrollmentStatus, Modality};
 
 #[derive(Accounts)]
 pub struct PromoteFromWaitlist<'info> {
@@ -34,35 +33,27 @@ pub struct PromoteFromWaitlist<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn promote_from_waitlist(ctx: Context<PromoteFromWaitlist>) -> Result<()> {
+// Acceptation d'une offre : crée l'inscription et met à jour les compteurs
+pub fn promote_from_waitlist(ctx: Context<PromoteFromWaitlist>, modality: Modality) -> Result<()> {
     let enrollment = &mut ctx.accounts.enrollment;
     let formation = &mut ctx.accounts.formation;
     let waitlist_entry = &mut ctx.accounts.waitlist_entry;
+    let now = Clock::get()?.unix_timestamp;
 
-    require!(
-        formation.current_students < formation.max_students,
-        AlyraError::FormationFull
-    );
-    require!(
-        waitlist_entry.status == WaitlistStatus::Promoted,
-        AlyraError::InvalidWaitlistStatus
-    );
+    waitlist_entry.accept_promotion(now, formation.promotion_timeout)?;
 
     // Mettre à jour l'inscription
     enrollment.formation = formation.key();
     enrollment.student = ctx.accounts.student.key();
     enrollment.status = EnrollmentStatus::Enrolled;
-    enrollment.position = formation.current_students;
-    enrollment.created_at = Clock::get()?.unix_timestamp;
-    enrollment.updated_at = Clock::get()?.unix_timestamp;
-
-    // Mettre à jour la liste d'attente
-    waitlist_entry.status = WaitlistStatus::Promoted;
-    waitlist_entry.updated_at = Clock::get()?.unix_timestamp;
+    (enrollment.position, enrollment.position_generation) = formation.assign_position()?;
+    enrollment.modality = modality;
+    formation.add_to_modality(modality, now)?;
+    enrollment.created_at = now;
+    enrollment.updated_at = now;
 
     // Mettre à jour la formation
-    formation.current_students = formation.current_students.checked_add(1).unwrap();
-    formation.current_waitlisted = formation.current_waitlisted.checked_sub(1).unwrap();
-    formation.updated_at = Clock::get()?.unix_timestamp;
+    formation.promote_from_waitlist()?;
+    formation.updated_at = now;
     Ok(())
-} 
\ No newline at end of file
+}
\ No newline at end of file
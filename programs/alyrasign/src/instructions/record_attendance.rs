@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
-use crate::state::{Session, Enrollment, Attendance};
-use crate::types::AttendanceStatus;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use crate::state::{Session, Enrollment, Attendance, Formation, ATTENDANCE_SPACE};
+use crate::types::{EnrollmentStatus, CheckInMethod, DisputeStatus};
 use crate::error::AlyraError;
+use crate::ed25519::verified_messages;
 
 #[derive(Accounts)]
 pub struct RecordAttendance<'info> {
@@ -11,36 +13,127 @@ pub struct RecordAttendance<'info> {
     #[account(mut)]
     pub session: Account<'info, Session>,
     
-    #[account(mut)]
+    #[account(mut, address = session.formation)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        mut,
+        seeds = [b"enrollment", formation.key().as_ref(), student.key().as_ref()],
+        bump
+    )]
     pub enrollment: Account<'info, Enrollment>,
     
     #[account(
-        init,
+        init_if_needed,
         payer = student,
-        space = 8 + std::mem::size_of::<Attendance>(),
+        space = ATTENDANCE_SPACE,
         seeds = [b"attendance", session.key().as_ref(), student.key().as_ref()],
         bump
     )]
     pub attendance: Account<'info, Attendance>,
     
+    /// CHECK: sysvar Instructions, lu pour retrouver la signature Ed25519 du code de pointage
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    
     pub system_program: Program<'info, System>,
 }
 
-pub fn record_attendance(
-    ctx: Context<RecordAttendance>,
-    status: AttendanceStatus,
-) -> Result<()> {
+pub fn record_attendance(ctx: Context<RecordAttendance>, code: Option<[u8; 32]>) -> Result<()> {
     require!(
-        ctx.accounts.enrollment.student == ctx.accounts.student.key(),
+        ctx.accounts.enrollment.student == ctx.accounts.student.key()
+            && ctx.accounts.enrollment.status == EnrollmentStatus::Enrolled,
         AlyraError::NotEnrolled
     );
+    require!(
+        ctx.accounts.formation.allows_self_check_in(),
+        AlyraError::SelfCheckInDisabled
+    );
+    require!(
+        ctx.accounts.formation.uses_attendance_accounts(),
+        AlyraError::AttendanceStorageMismatch
+    );
+    require!(
+        ctx.accounts.session.is_open_for_attendance(),
+        AlyraError::SessionFinalized
+    );
+
+    // Le statut est déduit de l'horloge, jamais fourni par l'étudiant
+    let now = Clock::get()?.unix_timestamp;
+    let late_threshold = ctx.accounts.formation.late_threshold;
+    let first_check_in = ctx.accounts.attendance.session == Pubkey::default();
+    let segment = if ctx.accounts.session.segments.is_empty() {
+        require!(first_check_in, AlyraError::AlreadyCheckedIn);
+        None
+    } else {
+        Some(ctx.accounts.session.segment_status_at(now, late_threshold)?)
+    };
+    let status = match segment {
+        Some((_, status)) => status,
+        None => ctx.accounts.session.attendance_status_at(now, late_threshold)?,
+    };
+
+    verify_presence(&mut ctx.accounts.session, &ctx.accounts.instructions, code, now)?;
 
     let attendance = &mut ctx.accounts.attendance;
-    attendance.session = ctx.accounts.session.key();
-    attendance.student = ctx.accounts.student.key();
-    attendance.status = status;
-    attendance.check_in_time = Clock::get()?.unix_timestamp;
-    attendance.created_at = Clock::get()?.unix_timestamp;
-    attendance.updated_at = Clock::get()?.unix_timestamp;
+    if first_check_in {
+        attendance.session = ctx.accounts.session.key();
+        attendance.student = ctx.accounts.student.key();
+        attendance.status = status;
+        attendance.check_in_time = now;
+        attendance.check_out_time = 0;
+        attendance.attended_seconds = 0;
+        attendance.left_early = false;
+        attendance.modality = ctx.accounts.enrollment.modality;
+        attendance.dispute_status = DisputeStatus::None;
+        attendance.correction_requests = 0;
+        attendance.created_at = now;
+        attendance.updated_at = now;
+
+        let session = &mut ctx.accounts.session;
+        session.add_attendance(&ctx.accounts.enrollment)?;
+    }
+
+    // Session découpée : chaque demi-journée est signée séparément
+    if let Some((index, status)) = segment {
+        attendance.sign_segment(index, status, now)?;
+    }
+
+    ctx.accounts.formation.lock_attendance_storage();
     Ok(())
-} 
\ No newline at end of file
+}
+
+// Preuve de présence selon la méthode de la session : code signé par le
+// formateur, ou maillon suivant de la chaîne de hachage fourni par l'étudiant
+pub(crate) fn verify_presence(
+    session: &mut Account<Session>,
+    instructions: &AccountInfo,
+    code: Option<[u8; 32]>,
+    now: i64,
+) -> Result<()> {
+    match session.check_in_method {
+        CheckInMethod::TrainerSignature => verify_trainer_code(session, instructions, now),
+        CheckInMethod::HashChain => {
+            let code = code.ok_or(AlyraError::InvalidCheckInCode)?;
+            session.accept_chain_code(code, now)
+        }
+    }
+}
+
+// Présence physique : une instruction Ed25519 antérieure de la transaction doit
+// vérifier le code signé par le formateur pour cette session et la fenêtre courante
+fn verify_trainer_code(
+    session: &mut Account<Session>,
+    instructions: &AccountInfo,
+    now: i64,
+) -> Result<()> {
+    let session_key = session.key();
+    let window = verified_messages(instructions)?
+        .iter()
+        .filter(|verified| session.is_acting_trainer(&verified.signer))
+        .filter_map(|verified| Session::check_in_code_window(&session_key, &verified.message))
+        .max()
+        .ok_or(AlyraError::InvalidCheckInCode)?;
+
+    session.accept_check_in_window(window, now)
+}
\ No newline at end of file
@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+use crate::state::{Session, Formation, TrainerSchedule, ScheduleEntry, Room};
+use crate::types::SessionStatus;
+use crate::error::AlyraError;
+use crate::config::{MIN_SESSION_DURATION, MAX_SESSION_DURATION, TRAINER_SCHEDULE_SEED};
+
+#[derive(Accounts)]
+pub struct RescheduleSession<'info> {
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+    
+    #[account(
+        mut,
+        seeds = [TRAINER_SCHEDULE_SEED, trainer.key().as_ref()],
+        bump
+    )]
+    pub trainer_schedule: Account<'info, TrainerSchedule>,
+    
+    // Salle réservée par la session, obligatoire si `session.room` est renseigné
+    #[account(mut, address = session.room @ AlyraError::InvalidRoomBooking)]
+    pub room: Option<Account<'info, Room>>,
+    
+    // Formation de la session, obligatoire avec la salle pour suivre ses réservations
+    #[account(mut, address = session.formation)]
+    pub formation: Option<Account<'info, Formation>>,
+    
+    // Planning du remplaçant, obligatoire si `session.substitute` est renseigné
+    #[account(
+        mut,
+        seeds = [TRAINER_SCHEDULE_SEED, session.substitute.as_ref()],
+        bump
+    )]
+    pub substitute_schedule: Option<Account<'info, TrainerSchedule>>,
+    
+    pub trainer: Signer<'info>,
+}
+
+pub fn reschedule_session(
+    ctx: Context<RescheduleSession>,
+    start_time: i64,
+    end_time: i64,
+) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        session.trainer == ctx.accounts.trainer.key(),
+        AlyraError::UnauthorizedAccess
+    );
+    require!(
+        session.status != SessionStatus::Cancelled,
+        AlyraError::SessionCancelled
+    );
+    require!(
+        now < session.start_time && session.attendance_count == 0,
+        AlyraError::SessionAlreadyStarted
+    );
+    require!(
+        start_time > now,
+        AlyraError::InvalidTimeRange
+    );
+
+    let duration = end_time - start_time;
+    require!(
+        (MIN_SESSION_DURATION..=MAX_SESSION_DURATION).contains(&duration),
+        AlyraError::InvalidSessionDuration
+    );
+
+    // Les plages d'émargement suivent la session et doivent tenir dans ses nouveaux horaires
+    let shift = start_time - session.start_time;
+    for segment in session.segments.iter_mut() {
+        segment.start_time += shift;
+        segment.end_time += shift;
+        require!(
+            segment.end_time <= end_time,
+            AlyraError::InvalidSessionSegments
+        );
+    }
+
+    let (old_start_time, old_end_time) = (session.start_time, session.end_time);
+    session.start_time = start_time;
+    session.end_time = end_time;
+    session.updated_at = now;
+
+    let entry = ScheduleEntry {
+        session: session.key(),
+        formation: session.formation,
+        start_time,
+        end_time,
+    };
+    let trainer_schedule = &mut ctx.accounts.trainer_schedule;
+    trainer_schedule.remove(&session.key());
+    trainer_schedule.insert(entry, now)?;
+
+    if session.substitute != Pubkey::default() {
+        let substitute_schedule = ctx.accounts.substitute_schedule
+            .as_mut()
+            .ok_or(AlyraError::InvalidSubstitute)?;
+        substitute_schedule.remove(&session.key());
+        substitute_schedule.insert(entry, now)?;
+    }
+
+    // La salle doit rester libre sur le nouveau créneau
+    if session.room != Pubkey::default() {
+        let room = ctx.accounts.room.as_mut().ok_or(AlyraError::InvalidRoomBooking)?;
+        room.release(&session.key());
+        room.book(entry, now)?;
+        room.updated_at = now;
+        let formation = ctx.accounts.formation.as_mut().ok_or(AlyraError::InvalidRoomBooking)?;
+        formation.reschedule_venue_booking(&session.key(), end_time);
+        formation.updated_at = now;
+    }
+
+    emit!(SessionRescheduledEvent {
+        session: session.key(),
+        formation: session.formation,
+        old_start_time,
+        old_end_time,
+        start_time,
+        end_time,
+    });
+    Ok(())
+}
+
+#[event]
+pub struct SessionRescheduledEvent {
+    pub session: Pubkey,
+    pub formation: Pubkey,
+    pub old_start_time: i64,
+    pub old_end_time: i64,
+    pub start_time: i64,
+    pub end_time: i64,
+}
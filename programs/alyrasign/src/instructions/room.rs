@@ -0,0 +1,231 @@
+use anchor_lang::prelude::*;
+use crate::state::{Session, Formation, Room, ScheduleEntry, VenueBooking, ROOM_SPACE};
+use crate::types::FormationType;
+use crate::error::AlyraError;
+use crate::config::{ROOM_SEED, STORAGE_SEED, MAX_ROOM_NAME_LENGTH};
+use crate::utils::{read_account, write_account};
+use crate::ProgramStorage;
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreateRoom<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = ROOM_SPACE,
+        seeds = [ROOM_SEED, name.as_bytes()],
+        bump
+    )]
+    pub room: Account<'info, Room>,
+    
+    #[account(
+        seeds = [STORAGE_SEED],
+        bump,
+        constraint = storage.admin == admin.key() @ AlyraError::UnauthorizedAccess
+    )]
+    pub storage: Account<'info, ProgramStorage>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRoom<'info> {
+    #[account(mut)]
+    pub room: Account<'info, Room>,
+    
+    #[account(
+        seeds = [STORAGE_SEED],
+        bump,
+        constraint = storage.admin == admin.key() @ AlyraError::UnauthorizedAccess
+    )]
+    pub storage: Account<'info, ProgramStorage>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BookRoom<'info> {
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+    
+    #[account(mut, address = session.formation)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(mut)]
+    pub room: Account<'info, Room>,
+    
+    pub trainer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseRoom<'info> {
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+    
+    #[account(mut, address = session.formation)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(mut, address = session.room @ AlyraError::InvalidRoomBooking)]
+    pub room: Account<'info, Room>,
+    
+    pub trainer: Signer<'info>,
+}
+
+pub fn create_room(ctx: Context<CreateRoom>, name: String, capacity: u8, equipment: u16) -> Result<()> {
+    require!(
+        name.len() <= MAX_ROOM_NAME_LENGTH,
+        AlyraError::RoomNameTooLong
+    );
+
+    let room = &mut ctx.accounts.room;
+    let now = Clock::get()?.unix_timestamp;
+    room.name = name;
+    room.capacity = capacity;
+    room.equipment = equipment;
+    room.active = true;
+    room.bookings = Vec::new();
+    room.created_at = now;
+    room.updated_at = now;
+    Ok(())
+}
+
+// Une salle désactivée garde ses réservations mais n'en accepte plus de nouvelles.
+// Un changement de capacité est répercuté sur les formations qui y ont une session
+// à venir, passées dans `remaining_accounts`, sans descendre sous leurs inscrits sur place.
+pub fn update_room<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdateRoom<'info>>,
+    capacity: u8,
+    equipment: u16,
+    active: bool,
+) -> Result<()> {
+    let room = &mut ctx.accounts.room;
+    let now = Clock::get()?.unix_timestamp;
+
+    if capacity != room.capacity {
+        for booking in room.bookings.iter().filter(|booking| booking.end_time >= now) {
+            require!(
+                ctx.remaining_accounts.iter().any(|info| info.key() == booking.formation),
+                AlyraError::InvalidRoomBooking
+            );
+        }
+        for formation_info in ctx.remaining_accounts.iter() {
+            let mut formation: Formation = read_account(formation_info)?;
+            if formation.update_venue_capacity(&room.key(), capacity, now) {
+                require!(
+                    capacity >= formation.current_onsite,
+                    AlyraError::RoomUnsuitable
+                );
+            }
+            formation.updated_at = now;
+            write_account(formation_info, &formation)?;
+        }
+    }
+
+    room.capacity = capacity;
+    room.equipment = equipment;
+    room.active = active;
+    room.updated_at = now;
+    Ok(())
+}
+
+pub fn book_room(ctx: Context<BookRoom>, required_equipment: u16) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let formation = &mut ctx.accounts.formation;
+    let room = &mut ctx.accounts.room;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        session.trainer == ctx.accounts.trainer.key(),
+        AlyraError::UnauthorizedAccess
+    );
+    require!(
+        matches!(formation.formation_type, FormationType::InPerson | FormationType::Hybrid),
+        AlyraError::RoomNotRequired
+    );
+    require!(
+        session.is_open_for_attendance() && now < session.start_time,
+        AlyraError::SessionAlreadyStarted
+    );
+    require!(
+        session.room == Pubkey::default(),
+        AlyraError::InvalidRoomBooking
+    );
+    // Seuls les étudiants sur place occupent la salle
+    require!(
+        room.capacity >= formation.current_onsite && room.has_equipment(required_equipment),
+        AlyraError::RoomUnsuitable
+    );
+
+    room.book(ScheduleEntry {
+        session: session.key(),
+        formation: formation.key(),
+        start_time: session.start_time,
+        end_time: session.end_time,
+    }, now)?;
+    room.updated_at = now;
+
+    // Les inscriptions et changements de modalité suivants restent dans la salle
+    formation.add_venue_booking(VenueBooking {
+        session: session.key(),
+        room: room.key(),
+        capacity: room.capacity,
+        end_time: session.end_time,
+    }, now)?;
+    formation.updated_at = now;
+
+    session.room = room.key();
+    session.updated_at = now;
+
+    emit!(RoomBookedEvent {
+        room: room.key(),
+        session: session.key(),
+        start_time: session.start_time,
+        end_time: session.end_time,
+    });
+    Ok(())
+}
+
+pub fn release_room(ctx: Context<ReleaseRoom>) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let formation = &mut ctx.accounts.formation;
+    let room = &mut ctx.accounts.room;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        session.trainer == ctx.accounts.trainer.key(),
+        AlyraError::UnauthorizedAccess
+    );
+
+    room.release(&session.key());
+    room.updated_at = now;
+
+    // La salle libérée ne plafonne plus les inscrits sur place
+    formation.remove_venue_booking(&session.key());
+    formation.updated_at = now;
+
+    session.room = Pubkey::default();
+    session.updated_at = now;
+
+    emit!(RoomReleasedEvent {
+        room: room.key(),
+        session: session.key(),
+    });
+    Ok(())
+}
+
+#[event]
+pub struct RoomBookedEvent {
+    pub room: Pubkey,
+    pub session: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct RoomReleasedEvent {
+    pub room: Pubkey,
+    pub session: Pubkey,
+}
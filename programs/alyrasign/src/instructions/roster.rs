@@ -0,0 +1,286 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use crate::state::{Session, Formation, Enrollment, SessionRoster};
+use crate::types::{AttendanceStatus, EnrollmentStatus, SessionStatus};
+use crate::error::AlyraError;
+use crate::config::{ROSTER_SEED, ROSTER_SIZE};
+use crate::instructions::record_attendance::verify_presence;
+use crate::instructions::finalize_session::SessionFinalizedEvent;
+use crate::utils::read_account;
+
+#[derive(Accounts)]
+pub struct InitSessionRoster<'info> {
+    #[account(mut)]
+    pub trainer: Signer<'info>,
+    
+    pub session: Account<'info, Session>,
+    
+    #[account(address = session.formation)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        init,
+        payer = trainer,
+        space = 8 + std::mem::size_of::<SessionRoster>(),
+        seeds = [ROSTER_SEED, session.key().as_ref()],
+        bump
+    )]
+    pub roster: AccountLoader<'info, SessionRoster>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecordRosterAttendance<'info> {
+    pub student: Signer<'info>,
+    
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+    
+    #[account(mut, address = session.formation)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        seeds = [b"enrollment", formation.key().as_ref(), student.key().as_ref()],
+        bump
+    )]
+    pub enrollment: Account<'info, Enrollment>,
+    
+    #[account(
+        mut,
+        seeds = [ROSTER_SEED, session.key().as_ref()],
+        bump
+    )]
+    pub roster: AccountLoader<'info, SessionRoster>,
+    
+    /// CHECK: sysvar Instructions, lu pour retrouver la signature Ed25519 du code de pointage
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+// Appel par le formateur : `remaining_accounts` contient l'`Enrollment` de chaque étudiant
+#[derive(Accounts)]
+pub struct MarkRosterBatch<'info> {
+    pub trainer: Signer<'info>,
+    
+    pub session: Account<'info, Session>,
+    
+    #[account(mut, address = session.formation)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        mut,
+        seeds = [ROSTER_SEED, session.key().as_ref()],
+        bump
+    )]
+    pub roster: AccountLoader<'info, SessionRoster>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeRoster<'info> {
+    pub trainer: Signer<'info>,
+    
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+    
+    #[account(address = session.formation)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        seeds = [ROSTER_SEED, session.key().as_ref()],
+        bump
+    )]
+    pub roster: AccountLoader<'info, SessionRoster>,
+}
+
+pub fn init_session_roster(ctx: Context<InitSessionRoster>) -> Result<()> {
+    require!(
+        ctx.accounts.session.trainer == ctx.accounts.trainer.key(),
+        AlyraError::UnauthorizedAccess
+    );
+    require!(
+        ctx.accounts.formation.uses_roster(),
+        AlyraError::AttendanceStorageMismatch
+    );
+
+    let mut roster = ctx.accounts.roster.load_init()?;
+    roster.session = ctx.accounts.session.key();
+    Ok(())
+}
+
+// Pointage individuel dans le registre : mêmes preuves de présence que `record_attendance`
+pub fn record_roster_attendance(ctx: Context<RecordRosterAttendance>, code: Option<[u8; 32]>) -> Result<()> {
+    let enrollment = &ctx.accounts.enrollment;
+    let formation = &ctx.accounts.formation;
+    require!(
+        enrollment.student == ctx.accounts.student.key()
+            && enrollment.status == EnrollmentStatus::Enrolled,
+        AlyraError::NotEnrolled
+    );
+    require!(
+        formation.allows_self_check_in(),
+        AlyraError::SelfCheckInDisabled
+    );
+    require!(
+        formation.uses_roster(),
+        AlyraError::AttendanceStorageMismatch
+    );
+    require!(
+        ctx.accounts.session.is_open_for_attendance(),
+        AlyraError::SessionFinalized
+    );
+    // Le registre ne conserve qu'un pointage par étudiant
+    require!(
+        ctx.accounts.session.segments.is_empty(),
+        AlyraError::InvalidSessionSegments
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let status = ctx.accounts.session.attendance_status_at(now, formation.late_threshold)?;
+
+    verify_presence(&mut ctx.accounts.session, &ctx.accounts.instructions, code, now)?;
+
+    let mut roster = ctx.accounts.roster.load_mut()?;
+    require!(
+        matches!(roster.status(enrollment), None | Some(AttendanceStatus::Absent)),
+        AlyraError::AlreadyCheckedIn
+    );
+    roster.record(enrollment, status, now)?;
+
+    ctx.accounts.formation.lock_attendance_storage();
+    Ok(())
+}
+
+pub fn mark_roster_batch<'info>(
+    ctx: Context<'_, '_, '_, 'info, MarkRosterBatch<'info>>,
+    statuses: Vec<AttendanceStatus>,
+) -> Result<()> {
+    let session = &ctx.accounts.session;
+    let formation = &ctx.accounts.formation;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        session.is_acting_trainer(&ctx.accounts.trainer.key()),
+        AlyraError::UnauthorizedAccess
+    );
+    require!(
+        formation.allows_roll_call(),
+        AlyraError::RollCallDisabled
+    );
+    require!(
+        formation.uses_roster(),
+        AlyraError::AttendanceStorageMismatch
+    );
+    require!(
+        session.is_open_for_attendance(),
+        AlyraError::SessionFinalized
+    );
+    require!(
+        now >= session.check_in_opens_at() && now <= session.check_in_closes_at(),
+        AlyraError::CheckInClosed
+    );
+    require!(
+        ctx.remaining_accounts.len() == statuses.len(),
+        AlyraError::InvalidAttendanceAccounts
+    );
+    require!(
+        !statuses.contains(&AttendanceStatus::Excused),
+        AlyraError::InvalidAttendanceStatus
+    );
+
+    let mut roster = ctx.accounts.roster.load_mut()?;
+    for (enrollment_info, status) in ctx.remaining_accounts.iter().zip(statuses) {
+        let enrollment: Enrollment = read_account(enrollment_info)?;
+        require!(
+            enrollment.formation == formation.key()
+                && enrollment.status == EnrollmentStatus::Enrolled,
+            AlyraError::NotEnrolled
+        );
+
+        let check_in_time = if status == AttendanceStatus::Absent { 0 } else { now };
+        roster.record(&enrollment, status, check_in_time)?;
+    }
+
+    ctx.accounts.formation.lock_attendance_storage();
+    Ok(())
+}
+
+// Clôture en une seule transaction : les positions occupées sans pointage
+// sont comptées absentes, sans compte à créer
+pub fn finalize_roster(ctx: Context<FinalizeRoster>) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let formation = &ctx.accounts.formation;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        session.is_acting_trainer(&ctx.accounts.trainer.key()),
+        AlyraError::UnauthorizedAccess
+    );
+    // Même borne que `finalize_session` : le pointage en retard reste possible
+    // jusqu'à `check_in_closes_at`
+    require!(
+        now > session.check_in_closes_at(),
+        AlyraError::SessionNotEnded
+    );
+    require!(
+        session.is_open_for_attendance(),
+        AlyraError::SessionFinalized
+    );
+    require!(
+        session.trainer_signed,
+        AlyraError::TrainerNotSigned
+    );
+
+    let roster = ctx.accounts.roster.load()?;
+    for position in 0..ROSTER_SIZE as u8 {
+        let occupied = formation.is_position_occupied(position);
+        match roster.entry(position) {
+            Some((status, generation)) if occupied && generation == formation.position_generation(position) => {
+                count_roster_status(session, status)?;
+            }
+            // Un étudiant désisté depuis reste compté s'il avait été enregistré ;
+            // le nouvel occupant de sa position, non enregistré, est absent
+            Some((status, _)) => {
+                count_roster_status(session, status)?;
+                if occupied {
+                    count_roster_status(session, AttendanceStatus::Absent)?;
+                }
+            }
+            None if occupied => count_roster_status(session, AttendanceStatus::Absent)?,
+            None => {}
+        }
+    }
+
+    let check_in_times: Vec<u8> = roster.check_in_times
+        .iter()
+        .flat_map(|time| time.to_le_bytes())
+        .collect();
+    session.attendance_hash = hashv(&[session.key().as_ref(), &roster.statuses, &check_in_times]).to_bytes();
+    session.status = SessionStatus::Finalized;
+    session.finalized_at = now;
+    session.updated_at = now;
+
+    emit!(SessionFinalizedEvent {
+        session: session.key(),
+        present_count: session.present_count,
+        late_count: session.late_count,
+        absent_count: session.absent_count,
+        left_early_count: session.left_early_count,
+        excused_count: session.excused_count,
+        attendance_rate: session.attendance_rate(),
+        attendance_hash: session.attendance_hash,
+    });
+    Ok(())
+}
+
+fn count_roster_status(session: &mut Session, status: AttendanceStatus) -> Result<()> {
+    let count = match status {
+        AttendanceStatus::Present => &mut session.present_count,
+        AttendanceStatus::Late => &mut session.late_count,
+        AttendanceStatus::Absent => &mut session.absent_count,
+        AttendanceStatus::Excused => &mut session.excused_count,
+    };
+    *count = count.checked_add(1).ok_or(AlyraError::Overflow)?;
+    Ok(())
+}
@@ -0,0 +1,222 @@
+use anchor_lang::prelude::*;
+use crate::state::{Session, SessionSeries, Formation, TrainerSchedule, ScheduleEntry, SESSION_SPACE, SESSION_SEED, SESSION_SERIES_SPACE, TRAINER_SCHEDULE_SPACE};
+use crate::types::{Recurrence, RecurrenceRule};
+use crate::error::AlyraError;
+use crate::config::{MAX_TITLE_LENGTH, MAX_DESCRIPTION_LENGTH, MIN_SESSION_DURATION, MAX_SESSION_DURATION, SESSION_SERIES_SEED, MAX_SERIES_SESSIONS, MAX_EXCLUDED_DATES, TRAINER_SCHEDULE_SEED};
+use crate::utils::{create_pda_account, write_account};
+
+// `remaining_accounts` contient les PDAs des prochaines sessions de la série,
+// dans l'ordre de `Formation.session_count`. Le client en passe autant que le
+// budget de calcul le permet, puis complète la série avec `continue_session_series`.
+#[derive(Accounts)]
+pub struct CreateSessionSeries<'info> {
+    #[account(mut)]
+    pub trainer: Signer<'info>,
+    
+    #[account(mut)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        init,
+        payer = trainer,
+        space = SESSION_SERIES_SPACE,
+        seeds = [SESSION_SERIES_SEED, formation.key().as_ref(), &formation.session_count.to_le_bytes()],
+        bump
+    )]
+    pub series: Account<'info, SessionSeries>,
+    
+    #[account(
+        init_if_needed,
+        payer = trainer,
+        space = TRAINER_SCHEDULE_SPACE,
+        seeds = [TRAINER_SCHEDULE_SEED, trainer.key().as_ref()],
+        bump
+    )]
+    pub trainer_schedule: Account<'info, TrainerSchedule>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ContinueSessionSeries<'info> {
+    #[account(mut)]
+    pub trainer: Signer<'info>,
+    
+    #[account(mut, address = series.formation)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(mut)]
+    pub series: Account<'info, SessionSeries>,
+    
+    #[account(
+        mut,
+        seeds = [TRAINER_SCHEDULE_SEED, trainer.key().as_ref()],
+        bump
+    )]
+    pub trainer_schedule: Account<'info, TrainerSchedule>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_session_series<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateSessionSeries<'info>>,
+    title: String,
+    description: String,
+    first_start_time: i64,
+    duration: i64,
+    recurrence: Recurrence,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        ctx.accounts.formation.trainer == ctx.accounts.trainer.key(),
+        AlyraError::UnauthorizedAccess
+    );
+    require!(
+        title.len() <= MAX_TITLE_LENGTH,
+        AlyraError::TitleTooLong
+    );
+    require!(
+        description.len() <= MAX_DESCRIPTION_LENGTH,
+        AlyraError::DescriptionTooLong
+    );
+    require!(
+        (MIN_SESSION_DURATION..=MAX_SESSION_DURATION).contains(&duration),
+        AlyraError::InvalidSessionDuration
+    );
+    require!(
+        first_start_time > now,
+        AlyraError::InvalidTimeRange
+    );
+    require!(
+        recurrence.count > 0
+            && recurrence.count <= MAX_SERIES_SESSIONS
+            && recurrence.excluded_dates.len() <= MAX_EXCLUDED_DATES,
+        AlyraError::InvalidRecurrence
+    );
+    // Une période nulle placerait toutes les sessions au même horaire
+    if let RecurrenceRule::EveryNDays(days) = recurrence.rule {
+        require!(days > 0, AlyraError::InvalidRecurrence);
+    }
+
+    let series = &mut ctx.accounts.series;
+    series.formation = ctx.accounts.formation.key();
+    series.trainer = ctx.accounts.trainer.key();
+    series.title = title;
+    series.description = description;
+    series.first_start_time = first_start_time;
+    series.duration = duration;
+    series.rule = recurrence.rule;
+    series.excluded_dates = recurrence.excluded_dates;
+    series.count = recurrence.count;
+    series.created = 0;
+    series.next_occurrence = 0;
+    series.created_at = now;
+    series.updated_at = now;
+
+    ctx.accounts.trainer_schedule.trainer = ctx.accounts.trainer.key();
+
+    create_series_sessions(
+        series,
+        &mut ctx.accounts.formation,
+        &mut ctx.accounts.trainer_schedule,
+        ctx.remaining_accounts,
+        &ctx.accounts.trainer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        now,
+    )
+}
+
+pub fn continue_session_series<'info>(
+    ctx: Context<'_, '_, '_, 'info, ContinueSessionSeries<'info>>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.series.trainer == ctx.accounts.trainer.key(),
+        AlyraError::UnauthorizedAccess
+    );
+
+    create_series_sessions(
+        &mut ctx.accounts.series,
+        &mut ctx.accounts.formation,
+        &mut ctx.accounts.trainer_schedule,
+        ctx.remaining_accounts,
+        &ctx.accounts.trainer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        Clock::get()?.unix_timestamp,
+    )
+}
+
+fn create_series_sessions<'info>(
+    series: &mut Account<'info, SessionSeries>,
+    formation: &mut Account<'info, Formation>,
+    trainer_schedule: &mut Account<'info, TrainerSchedule>,
+    session_accounts: &[AccountInfo<'info>],
+    trainer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    now: i64,
+) -> Result<()> {
+    require!(
+        session_accounts.len() <= (series.count - series.created) as usize,
+        AlyraError::InvalidSessionAccounts
+    );
+
+    let formation_key = formation.key();
+    for session_info in session_accounts {
+        let index = formation.session_count.to_le_bytes();
+        let (expected, bump) = Pubkey::find_program_address(
+            &[SESSION_SEED, formation_key.as_ref(), &index],
+            &crate::ID,
+        );
+        require_keys_eq!(session_info.key(), expected, AlyraError::InvalidSessionAccounts);
+
+        create_pda_account(
+            trainer,
+            session_info,
+            system_program,
+            SESSION_SPACE,
+            &[&[SESSION_SEED, formation_key.as_ref(), &index, &[bump]]],
+        )?;
+
+        let start_time = series.next_start_time()?;
+        let session = Session::new(
+            formation_key,
+            series.trainer,
+            series.title.clone(),
+            series.description.clone(),
+            start_time,
+            start_time + series.duration,
+            now,
+        );
+        write_account(session_info, &session)?;
+
+        trainer_schedule.insert(ScheduleEntry {
+            session: expected,
+            formation: formation_key,
+            start_time,
+            end_time: session.end_time,
+        }, now)?;
+
+        formation.session_count = formation.session_count.checked_add(1)
+            .ok_or(AlyraError::Overflow)?;
+        series.created += 1;
+
+        emit!(SeriesSessionCreatedEvent {
+            series: series.key(),
+            session: expected,
+            start_time,
+            index: series.created,
+        });
+    }
+
+    formation.updated_at = now;
+    series.updated_at = now;
+    Ok(())
+}
+
+#[event]
+pub struct SeriesSessionCreatedEvent {
+    pub series: Pubkey,
+    pub session: Pubkey,
+    pub start_time: i64,
+    pub index: u16,
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use crate::state::Session;
+use crate::types::{SessionSegment, SegmentRule};
+use crate::error::AlyraError;
+use crate::config::{MAX_SESSION_SEGMENTS, MAX_SEGMENT_NAME_LENGTH};
+
+#[derive(Accounts)]
+pub struct SetSessionSegments<'info> {
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+    
+    pub trainer: Signer<'info>,
+}
+
+// Découpe la session en plages d'émargement (une signature par demi-journée).
+// Une liste vide revient à une seule signature pour toute la session.
+pub fn set_session_segments(
+    ctx: Context<SetSessionSegments>,
+    segments: Vec<SessionSegment>,
+    segment_rule: SegmentRule,
+) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        session.trainer == ctx.accounts.trainer.key(),
+        AlyraError::UnauthorizedAccess
+    );
+    require!(
+        now < session.check_in_opens_at(),
+        AlyraError::SessionAlreadyStarted
+    );
+    require!(
+        segments.len() <= MAX_SESSION_SEGMENTS,
+        AlyraError::InvalidSessionSegments
+    );
+
+    // Plages triées, disjointes et comprises dans la session
+    let mut previous_end = session.start_time;
+    for segment in segments.iter() {
+        require!(
+            segment.name.len() <= MAX_SEGMENT_NAME_LENGTH,
+            AlyraError::InvalidSessionSegments
+        );
+        require!(
+            segment.start_time >= previous_end
+                && segment.start_time < segment.end_time
+                && segment.end_time <= session.end_time,
+            AlyraError::InvalidSessionSegments
+        );
+        previous_end = segment.end_time;
+    }
+
+    if let SegmentRule::AtLeast(minimum) = segment_rule {
+        require!(
+            minimum > 0 && minimum as usize <= segments.len(),
+            AlyraError::InvalidSessionSegments
+        );
+    }
+
+    session.segments = segments;
+    session.segment_rule = segment_rule;
+    session.updated_at = now;
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use crate::state::{Session, TrainerAttendance, TRAINER_ATTENDANCE_SPACE};
+use crate::error::AlyraError;
+use crate::config::TRAINER_ATTENDANCE_SEED;
+
+#[derive(Accounts)]
+pub struct SignTrainerAttendance<'info> {
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+    
+    #[account(
+        init_if_needed,
+        payer = trainer,
+        space = TRAINER_ATTENDANCE_SPACE,
+        seeds = [TRAINER_ATTENDANCE_SEED, session.key().as_ref()],
+        bump
+    )]
+    pub trainer_attendance: Account<'info, TrainerAttendance>,
+    
+    #[account(mut)]
+    pub trainer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn sign_trainer_attendance(ctx: Context<SignTrainerAttendance>) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let trainer = ctx.accounts.trainer.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        session.is_acting_trainer(&trainer),
+        AlyraError::UnauthorizedAccess
+    );
+    require!(
+        session.is_open_for_attendance(),
+        AlyraError::SessionFinalized
+    );
+    require!(
+        now >= session.check_in_opens_at(),
+        AlyraError::CheckInNotOpen
+    );
+    require!(
+        now <= session.check_in_closes_at(),
+        AlyraError::CheckInClosed
+    );
+
+    let trainer_attendance = &mut ctx.accounts.trainer_attendance;
+    if trainer_attendance.created_at == 0 {
+        trainer_attendance.session = session.key();
+        trainer_attendance.created_at = now;
+    }
+    trainer_attendance.sign(session, trainer, now)?;
+
+    session.trainer_signed = trainer_attendance.is_complete(session);
+    session.updated_at = now;
+
+    emit!(TrainerSignedEvent {
+        session: session.key(),
+        trainer,
+        segment: session.segment_at(now).map(|index| index as u8),
+        complete: session.trainer_signed,
+    });
+    Ok(())
+}
+
+#[event]
+pub struct TrainerSignedEvent {
+    pub session: Pubkey,
+    pub trainer: Pubkey,
+    pub segment: Option<u8>,
+    pub complete: bool,
+}
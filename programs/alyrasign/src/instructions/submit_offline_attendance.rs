@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use crate::state::{Session, Formation, Enrollment, Attendance, ATTENDANCE_SPACE};
+use crate::types::{AttendanceStatus, EnrollmentStatus};
+use crate::error::AlyraError;
+use crate::config::ATTENDANCE_SEED;
+use crate::ed25519::verified_messages;
+use crate::utils::{create_pda_account, read_account, write_account};
+
+// Transmission différée des pointages collectés hors ligne. Chaque étudiant a
+// signé avec son wallet le message `Session::offline_check_in` ; les instructions
+// Ed25519 qui précèdent celle-ci vérifient ces signatures. `remaining_accounts`
+// contient, pour chaque étudiant, son `Enrollment` puis son compte `Attendance`.
+#[derive(Accounts)]
+pub struct SubmitOfflineAttendance<'info> {
+    #[account(mut)]
+    pub trainer: Signer<'info>,
+    
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+    
+    #[account(mut, address = session.formation)]
+    pub formation: Account<'info, Formation>,
+    
+    /// CHECK: sysvar Instructions, lu pour retrouver les signatures Ed25519 des étudiants
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn submit_offline_attendance<'info>(
+    ctx: Context<'_, '_, '_, 'info, SubmitOfflineAttendance<'info>>,
+) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let formation = &ctx.accounts.formation;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        session.is_acting_trainer(&ctx.accounts.trainer.key()),
+        AlyraError::UnauthorizedAccess
+    );
+    require!(
+        formation.allows_self_check_in(),
+        AlyraError::SelfCheckInDisabled
+    );
+    require!(
+        formation.uses_attendance_accounts(),
+        AlyraError::AttendanceStorageMismatch
+    );
+    require!(
+        session.is_open_for_attendance(),
+        AlyraError::SessionFinalized
+    );
+    require!(
+        ctx.remaining_accounts.chunks_exact(2).remainder().is_empty(),
+        AlyraError::InvalidAttendanceAccounts
+    );
+
+    let session_key = session.key();
+    let signed_check_ins: Vec<(Pubkey, i64)> = verified_messages(&ctx.accounts.instructions)?
+        .iter()
+        .filter_map(|verified| {
+            Session::offline_check_in(&session_key, &verified.message)
+                .filter(|(student, _)| *student == verified.signer)
+        })
+        .collect();
+
+    for accounts in ctx.remaining_accounts.chunks(2) {
+        let (enrollment_info, attendance_info) = (&accounts[0], &accounts[1]);
+
+        let enrollment: Enrollment = read_account(enrollment_info)?;
+        require!(
+            enrollment.formation == formation.key()
+                && enrollment.status == EnrollmentStatus::Enrolled,
+            AlyraError::NotEnrolled
+        );
+
+        let (expected, bump) = Pubkey::find_program_address(
+            &[ATTENDANCE_SEED, session_key.as_ref(), enrollment.student.as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(attendance_info.key(), expected, AlyraError::InvalidAttendanceAccounts);
+
+        let (_, timestamp) = signed_check_ins
+            .iter()
+            .find(|(student, _)| *student == enrollment.student)
+            .copied()
+            .ok_or(AlyraError::InvalidCheckInCode)?;
+
+        // L'horodatage signé doit tomber dans la fenêtre de pointage et ne pas être futur
+        require!(timestamp <= now, AlyraError::InvalidTimeRange);
+        let status = session.attendance_status_at(timestamp, formation.late_threshold)?;
+        let segment = if session.segments.is_empty() {
+            None
+        } else {
+            Some(session.segment_status_at(timestamp, formation.late_threshold)?)
+        };
+
+        if attendance_info.data_is_empty() {
+            create_pda_account(
+                &ctx.accounts.trainer.to_account_info(),
+                attendance_info,
+                &ctx.accounts.system_program.to_account_info(),
+                ATTENDANCE_SPACE,
+                &[&[ATTENDANCE_SEED, session_key.as_ref(), enrollment.student.as_ref(), &[bump]]],
+            )?;
+
+            let mut attendance = Attendance::new(&enrollment, session_key, status, timestamp, now);
+            if let Some((index, status)) = segment {
+                attendance.status = status;
+                attendance.segment_check_ins[index] = timestamp;
+            }
+            write_account(attendance_info, &attendance)?;
+
+            session.add_attendance(&enrollment)?;
+            continue;
+        }
+
+        // Présence déjà connue : seule une plage non signée ou une absence
+        // marquée par le crank peut être complétée, sans faire échouer le lot
+        let mut attendance: Attendance = read_account(attendance_info)?;
+        match segment {
+            Some((index, status)) => {
+                if attendance.segment_check_ins[index] != 0 || attendance.check_out_time != 0 {
+                    continue;
+                }
+                attendance.sign_segment(index, status, timestamp)?;
+            }
+            None => {
+                if attendance.status != AttendanceStatus::Absent || attendance.check_in_time != 0 {
+                    continue;
+                }
+                attendance.status = status;
+                attendance.check_in_time = timestamp;
+            }
+        }
+        attendance.updated_at = now;
+        write_account(attendance_info, &attendance)?;
+    }
+
+    session.updated_at = now;
+
+    ctx.accounts.formation.lock_attendance_storage();
+    Ok(())
+}
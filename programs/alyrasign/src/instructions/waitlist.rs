@@ -1,7 +1,12 @@
+// Liste d'attente : offre -> acceptation (`promote_from_waitlist`), refus
+// (`decline_promotion`) ou expiration (`expire_promotion`). Les transitions
+// autorisées sont définies par `WaitlistEntry` dans `state.rs`.
 use anchor_lang::prelude::*;
-use crate::state::{Formation, WaitlistEntry};
-use crate::types::WaitlistStatus;
+use crate::state::{Formation, WaitlistEntry, WaitlistQueue, WaitlistSlot, Lottery};
+use crate::types::{WaitlistStatus, WaitlistTier, AdmissionMode};
 use crate::error::AlyraError;
+use crate::config::{WAITLIST_QUEUE_SEED, STORAGE_SEED, LOTTERY_SEED, MAX_LOTTERY_ENTRANTS};
+use crate::ProgramStorage;
 
 #[derive(Accounts)]
 #[instruction()]
@@ -22,14 +27,82 @@ pub struct JoinWaitlist<'info> {
     )]
     pub waitlist_entry: Account<'info, WaitlistEntry>,
     
+    #[account(
+        mut,
+        seeds = [WAITLIST_QUEUE_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub waitlist_queue: Account<'info, WaitlistQueue>,
+    
+    // Requis lorsque la formation est en mode tirage au sort
+    #[account(
+        seeds = [LOTTERY_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub lottery: Option<Account<'info, Lottery>>,
+    
     #[account(mut)]
     pub student: Signer<'info>,
     
     pub system_program: Program<'info, System>,
 }
 
+// Crank sans permission : propose la place libre à l'étudiant en tête de file
+#[derive(Accounts)]
+pub struct OfferPromotion<'info> {
+    #[account(mut)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        mut,
+        seeds = [WAITLIST_QUEUE_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub waitlist_queue: Account<'info, WaitlistQueue>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"waitlist",
+            formation.key().as_ref(),
+            waitlist_entry.student.as_ref()
+        ],
+        bump
+    )]
+    pub waitlist_entry: Account<'info, WaitlistEntry>,
+}
+
+// Crank sans permission : libère la place d'une offre restée sans réponse
+#[derive(Accounts)]
+pub struct ExpirePromotion<'info> {
+    #[account(mut)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        mut,
+        seeds = [WAITLIST_QUEUE_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub waitlist_queue: Account<'info, WaitlistQueue>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"waitlist",
+            formation.key().as_ref(),
+            waitlist_entry.student.as_ref()
+        ],
+        bump
+    )]
+    pub waitlist_entry: Account<'info, WaitlistEntry>,
+    
+    // Nouvelle tête de file, à qui la place est proposée dans la même transaction
+    #[account(mut)]
+    pub next_entry: Option<Account<'info, WaitlistEntry>>,
+}
+
 #[derive(Accounts)]
-pub struct ProcessPromotion<'info> {
+pub struct DeclinePromotion<'info> {
     #[account(mut)]
     pub formation: Account<'info, Formation>,
     
@@ -44,6 +117,17 @@ pub struct ProcessPromotion<'info> {
     )]
     pub waitlist_entry: Account<'info, WaitlistEntry>,
     
+    #[account(
+        mut,
+        seeds = [WAITLIST_QUEUE_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub waitlist_queue: Account<'info, WaitlistQueue>,
+    
+    // Nouvelle tête de file, à qui la place est proposée dans la même transaction
+    #[account(mut)]
+    pub next_entry: Option<Account<'info, WaitlistEntry>>,
+    
     #[account(mut)]
     pub student: Signer<'info>,
 }
@@ -61,42 +145,115 @@ pub fn join_waitlist(ctx: Context<JoinWaitlist>) -> Result<()> {
     waitlist_entry.student = ctx.accounts.student.key();
     waitlist_entry.position = formation.current_waitlisted;
     waitlist_entry.status = WaitlistStatus::Waiting;
+    // La priorité est attribuée par le formateur ou l'administrateur via `set_waitlist_tier`
+    waitlist_entry.tier = WaitlistTier::General;
     waitlist_entry.timestamp = Clock::get()?.unix_timestamp;
     waitlist_entry.created_at = Clock::get()?.unix_timestamp;
     waitlist_entry.updated_at = Clock::get()?.unix_timestamp;
 
-    formation.current_waitlisted = formation.current_waitlisted.checked_add(1).unwrap();
+    // En mode tirage au sort, l'ordre d'attente est celui du tirage : la date
+    // d'inscription retenue est la clôture des inscriptions décalée du rang.
+    // Les non-participants passent après tous les participants.
+    let joined_at = if formation.admission_mode == AdmissionMode::Lottery {
+        let lottery = ctx.accounts.lottery.as_ref().ok_or(AlyraError::InvalidAdmissionMode)?;
+        require!(lottery.drawn, AlyraError::LotteryNotDrawn);
+        match lottery.rank_of(&waitlist_entry.student) {
+            Some(rank) => {
+                require!(
+                    rank >= lottery.seats as usize,
+                    AlyraError::InvalidWaitlistStatus
+                );
+                lottery.registration_end + rank as i64
+            }
+            None => waitlist_entry.created_at
+                .max(lottery.registration_end + MAX_LOTTERY_ENTRANTS as i64),
+        }
+    } else {
+        waitlist_entry.created_at
+    };
+
+    ctx.accounts.waitlist_queue.insert(WaitlistSlot {
+        student: waitlist_entry.student,
+        tier: waitlist_entry.tier,
+        joined_at,
+    })?;
+
+    formation.current_waitlisted = formation.current_waitlisted.checked_add(1)
+        .ok_or(AlyraError::Overflow)?;
     formation.updated_at = Clock::get()?.unix_timestamp;
     Ok(())
 }
 
-pub fn process_promotion(ctx: Context<ProcessPromotion>) -> Result<()> {
-    let formation = &mut ctx.accounts.formation;
-    let waitlist_entry = &mut ctx.accounts.waitlist_entry;
-
+// Réserve une place pour l'étudiant en tête de file et lui adresse une offre.
+// Appelé par le crank `offer_promotion` ou directement lors d'un désistement.
+pub fn offer_seat(
+    formation: &mut Account<Formation>,
+    waitlist_queue: &mut Account<WaitlistQueue>,
+    waitlist_entry: &mut Account<WaitlistEntry>,
+) -> Result<()> {
     require!(
-        formation.current_students < formation.max_students,
-        AlyraError::FormationFull
+        waitlist_entry.formation == formation.key(),
+        AlyraError::InvalidWaitlistStatus
     );
     require!(
-        waitlist_entry.status == WaitlistStatus::Waiting,
-        AlyraError::InvalidWaitlistStatus
+        waitlist_queue.head() == Some(waitlist_entry.student),
+        AlyraError::NotFirstInLine
     );
 
-    waitlist_entry.status = WaitlistStatus::Promoted;
-    waitlist_entry.updated_at = Clock::get()?.unix_timestamp;
+    let now = Clock::get()?.unix_timestamp;
+    waitlist_entry.promote(now)?;
+    formation.reserve_seat()?;
+    waitlist_queue.remove(&waitlist_entry.student);
+    formation.updated_at = now;
+
+    emit!(PromotionOfferedEvent {
+        formation: formation.key(),
+        student: waitlist_entry.student,
+        offered_at: now,
+    });
     Ok(())
 }
 
-pub fn decline_promotion(ctx: Context<ProcessPromotion>) -> Result<()> {
+pub fn offer_promotion(ctx: Context<OfferPromotion>) -> Result<()> {
+    offer_seat(
+        &mut ctx.accounts.formation,
+        &mut ctx.accounts.waitlist_queue,
+        &mut ctx.accounts.waitlist_entry,
+    )
+}
+
+pub fn expire_promotion(ctx: Context<ExpirePromotion>) -> Result<()> {
+    let formation = &mut ctx.accounts.formation;
     let waitlist_entry = &mut ctx.accounts.waitlist_entry;
-    require!(
-        waitlist_entry.status == WaitlistStatus::Promoted,
-        AlyraError::InvalidWaitlistStatus
-    );
+    let now = Clock::get()?.unix_timestamp;
 
-    waitlist_entry.status = WaitlistStatus::Declined;
-    waitlist_entry.updated_at = Clock::get()?.unix_timestamp;
+    waitlist_entry.expire(now, formation.promotion_timeout)?;
+    formation.release_seat()?;
+    formation.updated_at = now;
+
+    emit!(PromotionExpiredEvent {
+        formation: formation.key(),
+        student: waitlist_entry.student,
+    });
+
+    if let Some(next_entry) = ctx.accounts.next_entry.as_mut() {
+        offer_seat(formation, &mut ctx.accounts.waitlist_queue, next_entry)?;
+    }
+    Ok(())
+}
+
+pub fn decline_promotion(ctx: Context<DeclinePromotion>) -> Result<()> {
+    let formation = &mut ctx.accounts.formation;
+    let waitlist_entry = &mut ctx.accounts.waitlist_entry;
+    let now = Clock::get()?.unix_timestamp;
+
+    waitlist_entry.decline_promotion(now)?;
+    formation.release_seat()?;
+    formation.updated_at = now;
+
+    if let Some(next_entry) = ctx.accounts.next_entry.as_mut() {
+        offer_seat(formation, &mut ctx.accounts.waitlist_queue, next_entry)?;
+    }
     Ok(())
 }
 
@@ -116,6 +273,13 @@ pub struct DropFromWaitlist<'info> {
     )]
     pub waitlist_entry: Account<'info, WaitlistEntry>,
     
+    #[account(
+        mut,
+        seeds = [WAITLIST_QUEUE_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub waitlist_queue: Account<'info, WaitlistQueue>,
+    
     #[account(mut)]
     pub student: Signer<'info>,
 }
@@ -129,16 +293,77 @@ pub fn drop_from_waitlist(ctx: Context<DropFromWaitlist>) -> Result<()> {
         AlyraError::UnauthorizedAccess
     );
 
-    match waitlist_entry.status {
-        WaitlistStatus::Waiting | WaitlistStatus::Promoted => {
-            formation.current_waitlisted = formation.current_waitlisted.checked_sub(1).unwrap();
-            waitlist_entry.status = WaitlistStatus::Expired;
-            waitlist_entry.updated_at = Clock::get()?.unix_timestamp;
-            formation.updated_at = Clock::get()?.unix_timestamp;
-            Ok(())
-        }
-        _ => Err(AlyraError::InvalidWaitlistStatus.into())
-    }
+    // Une offre en cours se refuse via `decline_promotion`
+    let now = Clock::get()?.unix_timestamp;
+    waitlist_entry.withdraw(now)?;
+    ctx.accounts.waitlist_queue.remove(&waitlist_entry.student);
+    formation.remove_from_waitlist()?;
+    formation.updated_at = now;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetWaitlistTier<'info> {
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"waitlist",
+            formation.key().as_ref(),
+            waitlist_entry.student.as_ref()
+        ],
+        bump
+    )]
+    pub waitlist_entry: Account<'info, WaitlistEntry>,
+    
+    #[account(
+        mut,
+        seeds = [WAITLIST_QUEUE_SEED, formation.key().as_ref()],
+        bump
+    )]
+    pub waitlist_queue: Account<'info, WaitlistQueue>,
+    
+    #[account(
+        seeds = [STORAGE_SEED],
+        bump
+    )]
+    pub storage: Account<'info, ProgramStorage>,
+    
+    pub authority: Signer<'info>,
+}
+
+pub fn set_waitlist_tier(ctx: Context<SetWaitlistTier>, tier: WaitlistTier) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == ctx.accounts.formation.trainer || authority == ctx.accounts.storage.admin,
+        AlyraError::UnauthorizedAccess
+    );
+
+    let waitlist_entry = &mut ctx.accounts.waitlist_entry;
+    require!(
+        waitlist_entry.status == WaitlistStatus::Waiting,
+        AlyraError::InvalidWaitlistStatus
+    );
+
+    // Repositionner l'étudiant dans la file en conservant sa date d'inscription
+    let waitlist_queue = &mut ctx.accounts.waitlist_queue;
+    let mut slot = waitlist_queue
+        .remove(&waitlist_entry.student)
+        .ok_or(AlyraError::InvalidWaitlistStatus)?;
+    slot.tier = tier;
+    waitlist_queue.insert(slot)?;
+
+    waitlist_entry.tier = tier;
+    waitlist_entry.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(WaitlistTierAssignedEvent {
+        formation: ctx.accounts.formation.key(),
+        student: waitlist_entry.student,
+        tier,
+        assigned_by: authority,
+    });
+    Ok(())
 }
 
 #[derive(Accounts)]
@@ -169,6 +394,27 @@ pub struct WaitlistDropEvent {
     pub position: u8,
 }
 
+#[event]
+pub struct PromotionOfferedEvent {
+    pub formation: Pubkey,
+    pub student: Pubkey,
+    pub offered_at: i64,
+}
+
+#[event]
+pub struct PromotionExpiredEvent {
+    pub formation: Pubkey,
+    pub student: Pubkey,
+}
+
+#[event]
+pub struct WaitlistTierAssignedEvent {
+    pub formation: Pubkey,
+    pub student: Pubkey,
+    pub tier: WaitlistTier,
+    pub assigned_by: Pubkey,
+}
+
 #[event]
 pub struct WaitlistReorganizedEvent {
     pub formation: Pubkey,
@@ -1,8 +1,8 @@
 use anchor_lang::prelude::*;
-use crate::state::{AccessRequest, Formation, Session, Enrollment};
-use crate::types::{Role, FormationType, RequestStatus, EnrollmentStatus};
-use crate::error::AlyraError;
+use crate::state::{AccessRequest, Formation};
+use crate::types::*;
 use crate::config::*;
+use crate::instructions::*;
 
 declare_id!("ATYrhRcGeQGKo43urjfgcWHkqMpDLYaCB9wmXodTC3Vu");
 
@@ -11,6 +11,9 @@ pub mod instructions;
 pub mod state;
 pub mod types;
 pub mod config;
+pub mod ed25519;
+pub mod utils;
+pub mod merkle;
 
 #[program]
 pub mod alyrasign {
@@ -34,18 +37,11 @@ pub mod alyrasign {
         email: String,
         message: String,
     ) -> Result<()> {
-        let access_request = &mut ctx.accounts.access_request;
-        access_request.user = ctx.accounts.user.key();
-        access_request.role = role;
-        access_request.name = name;
-        access_request.email = email;
-        access_request.message = message;
-        access_request.status = RequestStatus::Pending;
-        access_request.created_at = Clock::get()?.unix_timestamp;
-        access_request.updated_at = Clock::get()?.unix_timestamp;
-        Ok(())
+        ctx.accounts.process(role, name, email, message)
     }
 
+    // Formations et inscriptions
+
     pub fn create_formation(
         ctx: Context<CreateFormation>,
         title: String,
@@ -53,166 +49,305 @@ pub mod alyrasign {
         formation_type: FormationType,
         max_students: u8,
         waitlist_size: u8,
+        promotion_timeout: i64,
     ) -> Result<()> {
-        require!(
-            title.len() <= MAX_TITLE_LENGTH,
-            AlyraError::FieldTooLong
-        );
-        require!(
-            description.len() <= MAX_DESCRIPTION_LENGTH,
-            AlyraError::FieldTooLong
-        );
-        require!(
-            max_students <= MAX_FORMATION_CAPACITY,
-            AlyraError::FormationCapacityExceeded
-        );
-        require!(
-            waitlist_size <= MAX_WAITLIST_SIZE,
-            AlyraError::WaitlistFull
-        );
-
-        let formation = &mut ctx.accounts.formation;
-        formation.trainer = ctx.accounts.trainer.key();
-        formation.title = title;
-        formation.description = description;
-        formation.formation_type = formation_type;
-        formation.max_students = max_students;
-        formation.waitlist_size = waitlist_size;
-        formation.current_students = 0;
-        formation.current_waitlisted = 0;
-        formation.created_at = Clock::get()?.unix_timestamp;
-        formation.updated_at = Clock::get()?.unix_timestamp;
-        Ok(())
+        instructions::create_formation(
+            ctx,
+            title,
+            description,
+            formation_type,
+            max_students,
+            waitlist_size,
+            promotion_timeout,
+        )
+    }
+
+    pub fn enroll_in_formation(ctx: Context<EnrollInFormation>, modality: Modality) -> Result<()> {
+        instructions::enroll_in_formation(ctx, modality)
+    }
+
+    pub fn drop_enrollment(ctx: Context<DropEnrollment>) -> Result<()> {
+        instructions::drop_enrollment(ctx)
+    }
+
+    pub fn configure_hybrid_capacity(
+        ctx: Context<ConfigureHybridCapacity>,
+        onsite_capacity: u8,
+        remote_capacity: u8,
+    ) -> Result<()> {
+        instructions::configure_hybrid_capacity(ctx, onsite_capacity, remote_capacity)
+    }
+
+    pub fn switch_modality(ctx: Context<SwitchModality>, modality: Modality) -> Result<()> {
+        instructions::switch_modality(ctx, modality)
+    }
+
+    // Liste d'attente
+
+    pub fn join_waitlist(ctx: Context<JoinWaitlist>) -> Result<()> {
+        instructions::join_waitlist(ctx)
+    }
+
+    pub fn offer_promotion(ctx: Context<OfferPromotion>) -> Result<()> {
+        instructions::offer_promotion(ctx)
+    }
+
+    pub fn expire_promotion(ctx: Context<ExpirePromotion>) -> Result<()> {
+        instructions::expire_promotion(ctx)
+    }
+
+    pub fn decline_promotion(ctx: Context<DeclinePromotion>) -> Result<()> {
+        instructions::decline_promotion(ctx)
+    }
+
+    pub fn promote_from_waitlist(ctx: Context<PromoteFromWaitlist>, modality: Modality) -> Result<()> {
+        instructions::promote_from_waitlist(ctx, modality)
+    }
+
+    pub fn drop_from_waitlist(ctx: Context<DropFromWaitlist>) -> Result<()> {
+        instructions::drop_from_waitlist(ctx)
+    }
+
+    pub fn set_waitlist_tier(ctx: Context<SetWaitlistTier>, tier: WaitlistTier) -> Result<()> {
+        instructions::set_waitlist_tier(ctx, tier)
+    }
+
+    pub fn reorganize_waitlist(ctx: Context<ReorganizeWaitlist>) -> Result<()> {
+        instructions::reorganize_waitlist(ctx)
+    }
+
+    // Tirage au sort
+
+    pub fn open_lottery(
+        ctx: Context<OpenLottery>,
+        registration_end: i64,
+        commitment: [u8; 32],
+    ) -> Result<()> {
+        instructions::open_lottery(ctx, registration_end, commitment)
+    }
+
+    pub fn register_for_lottery(ctx: Context<RegisterForLottery>) -> Result<()> {
+        instructions::register_for_lottery(ctx)
+    }
+
+    pub fn close_lottery_registration(ctx: Context<CloseLotteryRegistration>) -> Result<()> {
+        instructions::close_lottery_registration(ctx)
+    }
+
+    pub fn record_lottery_slot_hash(ctx: Context<RecordLotterySlotHash>) -> Result<()> {
+        instructions::record_lottery_slot_hash(ctx)
+    }
+
+    pub fn draw_lottery(ctx: Context<DrawLottery>, secret: [u8; 32]) -> Result<()> {
+        instructions::draw_lottery(ctx, secret)
+    }
+
+    pub fn pin_lottery_fallback_slot(ctx: Context<PinLotteryFallbackSlot>) -> Result<()> {
+        instructions::pin_lottery_fallback_slot(ctx)
     }
 
+    pub fn force_draw_lottery(ctx: Context<ForceDrawLottery>) -> Result<()> {
+        instructions::force_draw_lottery(ctx)
+    }
+
+    pub fn release_lottery_seats(ctx: Context<ReleaseLotterySeats>) -> Result<()> {
+        instructions::release_lottery_seats(ctx)
+    }
+
+    // Sessions et planning
+
     pub fn create_session(
         ctx: Context<CreateSession>,
         title: String,
         description: String,
         start_time: i64,
         end_time: i64,
+        hash_chain: Option<HashChainCommitment>,
     ) -> Result<()> {
-        require!(
-            title.len() <= MAX_TITLE_LENGTH,
-            AlyraError::FieldTooLong
-        );
-        require!(
-            description.len() <= MAX_DESCRIPTION_LENGTH,
-            AlyraError::FieldTooLong
-        );
-        
-        let duration = end_time - start_time;
-        require!(
-            duration >= MIN_SESSION_DURATION && duration <= MAX_SESSION_DURATION,
-            AlyraError::InvalidTimeRange
-        );
-
-        let session = &mut ctx.accounts.session;
-        session.formation = ctx.accounts.formation.key();
-        session.trainer = ctx.accounts.trainer.key();
-        session.title = title;
-        session.description = description;
-        session.start_time = start_time;
-        session.end_time = end_time;
-        session.created_at = Clock::get()?.unix_timestamp;
-        session.updated_at = Clock::get()?.unix_timestamp;
-        Ok(())
+        instructions::create_session(ctx, title, description, start_time, end_time, hash_chain)
     }
 
-    pub fn enroll_in_formation(ctx: Context<EnrollInFormation>) -> Result<()> {
-        let enrollment = &mut ctx.accounts.enrollment;
-        let formation = &mut ctx.accounts.formation;
+    pub fn create_session_series<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateSessionSeries<'info>>,
+        title: String,
+        description: String,
+        first_start_time: i64,
+        duration: i64,
+        recurrence: Recurrence,
+    ) -> Result<()> {
+        instructions::create_session_series(ctx, title, description, first_start_time, duration, recurrence)
+    }
 
-        require!(
-            formation.current_students < formation.max_students,
-            AlyraError::FormationFull
-        );
+    pub fn continue_session_series<'info>(
+        ctx: Context<'_, '_, '_, 'info, ContinueSessionSeries<'info>>,
+    ) -> Result<()> {
+        instructions::continue_session_series(ctx)
+    }
 
-        enrollment.formation = formation.key();
-        enrollment.student = ctx.accounts.student.key();
-        enrollment.status = EnrollmentStatus::Enrolled;
-        enrollment.position = formation.current_students;
-        enrollment.created_at = Clock::get()?.unix_timestamp;
-        enrollment.updated_at = Clock::get()?.unix_timestamp;
+    pub fn reschedule_session(
+        ctx: Context<RescheduleSession>,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        instructions::reschedule_session(ctx, start_time, end_time)
+    }
 
-        formation.current_students = formation.current_students.checked_add(1).unwrap();
-        formation.updated_at = Clock::get()?.unix_timestamp;
-        Ok(())
+    pub fn cancel_session(ctx: Context<CancelSession>, reason: String) -> Result<()> {
+        instructions::cancel_session(ctx, reason)
     }
-}
 
-#[derive(Accounts)]
-pub struct RequestAccess<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
-    
-    #[account(
-        init,
-        payer = user,
-        space = 8 + std::mem::size_of::<AccessRequest>(),
-        seeds = [b"request", user.key().as_ref()],
-        bump
-    )]
-    pub access_request: Account<'info, AccessRequest>,
-    
-    pub system_program: Program<'info, System>,
-}
+    pub fn set_session_segments(
+        ctx: Context<SetSessionSegments>,
+        segments: Vec<SessionSegment>,
+        segment_rule: SegmentRule,
+    ) -> Result<()> {
+        instructions::set_session_segments(ctx, segments, segment_rule)
+    }
 
-#[derive(Accounts)]
-pub struct CreateFormation<'info> {
-    #[account(mut)]
-    pub trainer: Signer<'info>,
-    
-    #[account(
-        init,
-        payer = trainer,
-        space = 8 + std::mem::size_of::<Formation>(),
-        seeds = [b"formation", trainer.key().as_ref()],
-        bump
-    )]
-    pub formation: Account<'info, Formation>,
-    
-    pub system_program: Program<'info, System>,
-}
+    pub fn assign_substitute(ctx: Context<AssignSubstitute>, substitute: Pubkey) -> Result<()> {
+        instructions::assign_substitute(ctx, substitute)
+    }
 
-#[derive(Accounts)]
-pub struct CreateSession<'info> {
-    #[account(mut)]
-    pub trainer: Signer<'info>,
-    
-    #[account(mut)]
-    pub formation: Account<'info, Formation>,
-    
-    #[account(
-        init,
-        payer = trainer,
-        space = 8 + std::mem::size_of::<Session>(),
-        seeds = [b"session", formation.key().as_ref()],
-        bump
-    )]
-    pub session: Account<'info, Session>,
-    
-    pub system_program: Program<'info, System>,
-}
+    // Salles
 
-#[derive(Accounts)]
-pub struct EnrollInFormation<'info> {
-    #[account(mut)]
-    pub student: Signer<'info>,
-    
-    #[account(mut)]
-    pub formation: Account<'info, Formation>,
-    
-    #[account(
-        init,
-        payer = student,
-        space = 8 + std::mem::size_of::<Enrollment>(),
-        seeds = [b"enrollment", formation.key().as_ref(), student.key().as_ref()],
-        bump
-    )]
-    pub enrollment: Account<'info, Enrollment>,
-    
-    pub system_program: Program<'info, System>,
+    pub fn create_room(ctx: Context<CreateRoom>, name: String, capacity: u8, equipment: u16) -> Result<()> {
+        instructions::create_room(ctx, name, capacity, equipment)
+    }
+
+    pub fn update_room<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateRoom<'info>>,
+        capacity: u8,
+        equipment: u16,
+        active: bool,
+    ) -> Result<()> {
+        instructions::update_room(ctx, capacity, equipment, active)
+    }
+
+    pub fn book_room(ctx: Context<BookRoom>, required_equipment: u16) -> Result<()> {
+        instructions::book_room(ctx, required_equipment)
+    }
+
+    pub fn release_room(ctx: Context<ReleaseRoom>) -> Result<()> {
+        instructions::release_room(ctx)
+    }
+
+    // Présences
+
+    pub fn configure_attendance(
+        ctx: Context<ConfigureAttendance>,
+        late_threshold: i64,
+        attendance_mode: AttendanceMode,
+        attendance_storage: AttendanceStorage,
+    ) -> Result<()> {
+        instructions::configure_attendance(ctx, late_threshold, attendance_mode, attendance_storage)
+    }
+
+    pub fn advance_code_chain(ctx: Context<AdvanceCodeChain>, link: [u8; 32], window: i64) -> Result<()> {
+        instructions::advance_code_chain(ctx, link, window)
+    }
+
+    pub fn record_attendance(ctx: Context<RecordAttendance>, code: Option<[u8; 32]>) -> Result<()> {
+        instructions::record_attendance(ctx, code)
+    }
+
+    pub fn mark_attendance_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, MarkAttendanceBatch<'info>>,
+        statuses: Vec<AttendanceStatus>,
+    ) -> Result<()> {
+        instructions::mark_attendance_batch(ctx, statuses)
+    }
+
+    pub fn submit_offline_attendance<'info>(
+        ctx: Context<'_, '_, '_, 'info, SubmitOfflineAttendance<'info>>,
+    ) -> Result<()> {
+        instructions::submit_offline_attendance(ctx)
+    }
+
+    pub fn check_out(ctx: Context<CheckOut>) -> Result<()> {
+        instructions::check_out(ctx)
+    }
+
+    pub fn init_session_roster(ctx: Context<InitSessionRoster>) -> Result<()> {
+        instructions::init_session_roster(ctx)
+    }
+
+    pub fn record_roster_attendance(ctx: Context<RecordRosterAttendance>, code: Option<[u8; 32]>) -> Result<()> {
+        instructions::record_roster_attendance(ctx, code)
+    }
+
+    pub fn mark_roster_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, MarkRosterBatch<'info>>,
+        statuses: Vec<AttendanceStatus>,
+    ) -> Result<()> {
+        instructions::mark_roster_batch(ctx, statuses)
+    }
+
+    pub fn finalize_roster(ctx: Context<FinalizeRoster>) -> Result<()> {
+        instructions::finalize_roster(ctx)
+    }
+
+    pub fn init_attendance_accumulator(ctx: Context<InitAttendanceAccumulator>) -> Result<()> {
+        instructions::init_attendance_accumulator(ctx)
+    }
+
+    pub fn record_accumulated_attendance(
+        ctx: Context<RecordAccumulatedAttendance>,
+        code: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::record_accumulated_attendance(ctx, code)
+    }
+
+    pub fn mark_absent_leaves<'info>(ctx: Context<'_, '_, '_, 'info, MarkAbsentLeaves<'info>>) -> Result<()> {
+        instructions::mark_absent_leaves(ctx)
+    }
+
+    pub fn sign_trainer_attendance(ctx: Context<SignTrainerAttendance>) -> Result<()> {
+        instructions::sign_trainer_attendance(ctx)
+    }
+
+    pub fn fund_rent_pool(ctx: Context<FundRentPool>, amount: u64) -> Result<()> {
+        instructions::fund_rent_pool(ctx, amount)
+    }
+
+    pub fn mark_absent<'info>(ctx: Context<'_, '_, '_, 'info, MarkAbsent<'info>>) -> Result<()> {
+        instructions::mark_absent(ctx)
+    }
+
+    pub fn finalize_session<'info>(
+        ctx: Context<'_, '_, '_, 'info, FinalizeSession<'info>>,
+    ) -> Result<()> {
+        instructions::finalize_session(ctx)
+    }
+
+    // Contestations et justificatifs
+
+    pub fn open_dispute(ctx: Context<OpenDispute>, evidence_hash: [u8; 32]) -> Result<()> {
+        instructions::open_dispute(ctx, evidence_hash)
+    }
+
+    pub fn resolve_dispute(
+        ctx: Context<ResolveDispute>,
+        new_status: AttendanceStatus,
+        reason: String,
+    ) -> Result<()> {
+        instructions::resolve_dispute(ctx, new_status, reason)
+    }
+
+    pub fn submit_justification(
+        ctx: Context<SubmitJustification>,
+        justification_hash: [u8; 32],
+        justification_uri: String,
+    ) -> Result<()> {
+        instructions::submit_justification(ctx, justification_hash, justification_uri)
+    }
+
+    pub fn review_justification(
+        ctx: Context<ReviewJustification>,
+        accept: bool,
+        reason: String,
+    ) -> Result<()> {
+        instructions::review_justification(ctx, accept, reason)
+    }
 }
 
 #[error_code]
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use crate::types::{AttendanceStatus, Modality};
+use crate::config::MERKLE_DEPTH;
+
+// Préfixes distinguant feuilles et nœuds internes, pour qu'un nœud ne puisse
+// pas être présenté comme une feuille
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+// Contenu d'une feuille de l'accumulateur, publié dans `AttendanceLeafAppendedEvent`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct AttendanceLeaf {
+    pub formation: Pubkey,
+    pub session: Pubkey,
+    pub student: Pubkey,
+    pub status: AttendanceStatus,
+    pub modality: Modality,
+    pub check_in_time: i64,
+}
+
+impl AttendanceLeaf {
+    pub fn hash(&self) -> Result<[u8; 32]> {
+        let data = self.try_to_vec()?;
+        Ok(hashv(&[LEAF_PREFIX, &data]).to_bytes())
+    }
+}
+
+// Racine d'un arbre de profondeur `MERKLE_DEPTH` dont toutes les feuilles sont nulles
+pub fn empty_root() -> [u8; 32] {
+    (0..MERKLE_DEPTH).fold([0u8; 32], |zero, _| hash_node(&zero, &zero))
+}
+
+pub fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hashv(&[NODE_PREFIX, left, right]).to_bytes()
+}
+
+// Vérification hors chaîne : `proof` contient les `MERKLE_DEPTH` nœuds frères,
+// de la feuille vers la racine, les positions encore vides valant l'arbre nul
+pub fn verify_proof(root: &[u8; 32], leaf: &AttendanceLeaf, index: u64, proof: &[[u8; 32]]) -> bool {
+    if proof.len() != MERKLE_DEPTH || index >= 1u64 << MERKLE_DEPTH {
+        return false;
+    }
+
+    let Ok(mut node) = leaf.hash() else {
+        return false;
+    };
+    let mut index = index;
+    for sibling in proof {
+        node = if index & 1 == 0 {
+            hash_node(&node, sibling)
+        } else {
+            hash_node(sibling, &node)
+        };
+        index /= 2;
+    }
+    node == *root
+}
@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
-use crate::types::{Role, FormationType, RequestStatus, FormationStatus, EnrollmentStatus, AttendanceStatus, WaitlistStatus};
+use crate::types::{Role, FormationType, RequestStatus, FormationStatus, EnrollmentStatus, AttendanceStatus, WaitlistStatus, WaitlistTier, AdmissionMode, CheckInMethod, AttendanceMode, AttendanceStorage, Modality, SessionSegment, SegmentRule, SessionStatus, DisputeStatus, RecurrenceRule, JustificationStatus};
 use crate::error::AlyraError;
+use crate::merkle;
+use anchor_lang::solana_program::hash::{hash, hashv};
+use crate::config::{MAX_WAITLIST_SIZE, MAX_LOTTERY_ENTRANTS, CHECK_IN_OPEN_MARGIN, SESSION_TIMEOUT, CHECK_IN_CODE_WINDOW, MAX_CODE_CHAIN_STEPS, CHECK_IN_CODE_DOMAIN, OFFLINE_CHECK_IN_DOMAIN, EARLY_DEPARTURE_TOLERANCE, MAX_TITLE_LENGTH, MAX_DESCRIPTION_LENGTH, MAX_SESSION_SEGMENTS, MAX_SEGMENT_NAME_LENGTH, MAX_CORRECTION_REQUESTS, MAX_CORRECTION_REASON_LENGTH, MAX_JUSTIFICATION_URI_LENGTH, ROSTER_SIZE, ROSTER_BITMAP_LEN, MERKLE_DEPTH, MAX_EXCLUDED_DATES, SECONDS_PER_DAY, MAX_SCHEDULE_ENTRIES, MAX_ROOM_NAME_LENGTH, MAX_ROOM_BOOKINGS, MAX_VENUE_BOOKINGS};
 
 // Seeds
 pub const REQUEST_SEED: &[u8] = b"request";
@@ -30,6 +33,21 @@ pub const FORMATION_SPACE: usize = 8 + // discriminator
     8 + // waitlist_size
     8 + // current_students
     8 + // current_waitlisted
+    1 + // pending_promotions
+    8 + // promotion_timeout
+    1 + // admission_mode
+    8 + // late_threshold
+    1 + // attendance_mode
+    1 + // attendance_storage
+    1 + // attendance_recorded
+    ROSTER_BITMAP_LEN + // occupied_positions
+    4 * ROSTER_SIZE + // position_generations
+    4 + // session_count
+    1 + // onsite_capacity
+    1 + // remote_capacity
+    1 + // current_onsite
+    1 + // current_remote
+    VENUE_BOOKING_SPACE * MAX_VENUE_BOOKINGS + // venue_bookings
     1 + // status
     8 + // created_at
     8; // updated_at
@@ -37,10 +55,34 @@ pub const FORMATION_SPACE: usize = 8 + // discriminator
 pub const SESSION_SPACE: usize = 8 + // discriminator
     32 + // formation pubkey
     32 + // trainer pubkey
-    100 + // title
-    500 + // description
+    4 + MAX_TITLE_LENGTH + // title
+    4 + MAX_DESCRIPTION_LENGTH + // description
     8 + // start_time
     8 + // end_time
+    8 + // last_code_window
+    1 + // check_in_method
+    32 + // code_anchor
+    8 + // code_anchor_window
+    4 + // code_chain_length
+    4 + MAX_SESSION_SEGMENTS * (4 + MAX_SEGMENT_NAME_LENGTH + 8 + 8) + // segments
+    2 + // segment_rule
+    1 + // status
+    4 + // attendance_count
+    4 * ROSTER_SIZE + // recorded_generations
+    4 + // finalized_count
+    4 + // present_count
+    4 + // late_count
+    4 + // absent_count
+    4 + // excused_count
+    4 + // left_early_count
+    1 + // trainer_signed
+    32 + // last_finalized_student
+    32 + // room
+    32 + // substitute
+    32 + // substitute_assigned_by
+    8 + // substitute_assigned_at
+    32 + // attendance_hash
+    8 + // finalized_at
     8 + // created_at
     8; // updated_at
 
@@ -49,6 +91,8 @@ pub const ENROLLMENT_SPACE: usize = 8 + // discriminator
     32 + // student pubkey
     1 + // status
     1 + // position
+    4 + // position_generation
+    1 + // modality
     8 + // created_at
     8; // updated_at
 
@@ -57,6 +101,18 @@ pub const ATTENDANCE_SPACE: usize = 8 + // discriminator
     32 + // student pubkey
     1 + // status
     8 + // check_in_time
+    8 + // check_out_time
+    8 + // attended_seconds
+    1 + // left_early
+    8 * MAX_SESSION_SEGMENTS + // segment_check_ins
+    1 + // modality
+    1 + // dispute_status
+    1 + // correction_requests
+    32 + // evidence_hash
+    8 + // disputed_at
+    32 + // justification_hash
+    4 + MAX_JUSTIFICATION_URI_LENGTH + // justification_uri
+    1 + // justification_status
     8 + // created_at
     8; // updated_at
 
@@ -65,10 +121,86 @@ pub const WAITLIST_SPACE: usize = 8 + // discriminator
     32 + // formation pubkey
     1 + // position
     1 + // status
+    1 + // tier
     8 + // timestamp
     8 + // created_at
     8; // updated_at
 
+pub const WAITLIST_QUEUE_SPACE: usize = 8 + // discriminator
+    32 + // formation pubkey
+    4 + (32 + 1 + 8) * MAX_WAITLIST_SIZE as usize; // slots (student, tier, joined_at)
+
+pub const LOTTERY_SPACE: usize = 8 + // discriminator
+    32 + // formation pubkey
+    32 + // commitment
+    8 + // registration_end
+    8 + // target_slot
+    8 + // reveal_deadline
+    8 + // fallback_slot
+    4 + 32 * MAX_LOTTERY_ENTRANTS as usize + // entrants
+    4 + MAX_LOTTERY_ENTRANTS as usize + // ranking
+    1 + // seats
+    1 + // claimed_count
+    32 + // claimed
+    1 + // seats_released
+    1 + // drawn
+    1 + // revealed
+    32 + // secret
+    8 + // slot
+    32 + // slot_hash
+    32 + // seed
+    8 + // drawn_at
+    8 + // created_at
+    8; // updated_at
+
+pub const TRAINER_ATTENDANCE_SPACE: usize = 8 + // discriminator
+    32 + // session pubkey
+    32 + // trainer pubkey
+    8 + // signed_at
+    8 * MAX_SESSION_SEGMENTS + // segment_signed_at
+    32 * MAX_SESSION_SEGMENTS + // segment_signers
+    8 + // created_at
+    8; // updated_at
+
+pub const ACCUMULATOR_SPACE: usize = 8 + // discriminator
+    32 + // formation pubkey
+    32 + // root
+    8 + // leaf_count
+    32 * MERKLE_DEPTH; // frontier
+
+pub const SESSION_SERIES_SPACE: usize = 8 + // discriminator
+    32 + // formation pubkey
+    32 + // trainer pubkey
+    4 + MAX_TITLE_LENGTH + // title
+    4 + MAX_DESCRIPTION_LENGTH + // description
+    8 + // first_start_time
+    8 + // duration
+    3 + // rule
+    4 + 8 * MAX_EXCLUDED_DATES + // excluded_dates
+    2 + // count
+    2 + // created
+    4 + // next_occurrence
+    8 + // created_at
+    8; // updated_at
+
+pub const TRAINER_SCHEDULE_SPACE: usize = 8 + // discriminator
+    32 + // trainer pubkey
+    4 + (32 + 32 + 8 + 8) * MAX_SCHEDULE_ENTRIES; // entries (session, formation, start_time, end_time)
+
+pub const ROOM_SPACE: usize = 8 + // discriminator
+    4 + MAX_ROOM_NAME_LENGTH + // name
+    1 + // capacity
+    2 + // equipment
+    1 + // active
+    4 + (32 + 32 + 8 + 8) * MAX_ROOM_BOOKINGS + // bookings
+    8 + // created_at
+    8; // updated_at
+
+pub const CORRECTION_LOG_SPACE: usize = 8 + // discriminator
+    32 + // session pubkey
+    32 + // student pubkey
+    4 + (32 + 1 + 1 + 32 + 4 + MAX_CORRECTION_REASON_LENGTH + 8) * MAX_CORRECTION_REQUESTS; // entries
+
 #[account]
 pub struct AccessRequest {
     pub user: Pubkey,
@@ -91,6 +223,29 @@ pub struct Formation {
     pub waitlist_size: u8,
     pub current_students: u8,
     pub current_waitlisted: u8,
+    pub pending_promotions: u8,
+    pub promotion_timeout: i64,
+    pub admission_mode: AdmissionMode,
+    pub late_threshold: i64,
+    pub attendance_mode: AttendanceMode,
+    pub attendance_storage: AttendanceStorage,
+    // Au moins une présence enregistrée, quel que soit le mode de stockage
+    pub attendance_recorded: bool,
+    // Positions attribuées aux inscrits, qui indexent le registre des sessions
+    pub occupied_positions: [u8; ROSTER_BITMAP_LEN],
+    // Nombre d'attributions de chaque position : une présence de session n'est
+    // rattachée à l'occupant courant que si elle porte la même génération
+    pub position_generations: [u32; ROSTER_SIZE],
+    // Nombre de sessions créées, utilisé dans les seeds de chaque session
+    pub session_count: u32,
+    // Capacités par modalité, en plus de `max_students` (formations hybrides)
+    pub onsite_capacity: u8,
+    pub remote_capacity: u8,
+    pub current_onsite: u8,
+    pub current_remote: u8,
+    // Salles réservées pour les sessions de la formation ; la plus petite des
+    // réservations non terminées plafonne les inscrits sur place
+    pub venue_bookings: [VenueBooking; MAX_VENUE_BOOKINGS],
     pub status: FormationStatus,
     pub created_at: i64,
     pub updated_at: i64,
@@ -104,6 +259,110 @@ pub struct Session {
     pub description: String,
     pub start_time: i64,
     pub end_time: i64,
+    // Dernière fenêtre de code de pointage acceptée : les codes plus anciens sont refusés
+    pub last_code_window: i64,
+    pub check_in_method: CheckInMethod,
+    // Dernier maillon connu de la chaîne de hachage et sa fenêtre (-1 pour la queue)
+    pub code_anchor: [u8; 32],
+    pub code_anchor_window: i64,
+    pub code_chain_length: u32,
+    // Vide : la session forme une seule plage d'émargement
+    pub segments: Vec<SessionSegment>,
+    pub segment_rule: SegmentRule,
+    pub status: SessionStatus,
+    // Nombre de comptes Attendance créés pour la session
+    pub attendance_count: u32,
+    // Génération de la position de chaque inscrit ayant une présence enregistrée
+    // (compte Attendance ou feuille de l'accumulateur) ou non concerné par la
+    // session, 0 sinon
+    pub recorded_generations: [u32; ROSTER_SIZE],
+    // Clôture : comptes déjà parcourus, triés par étudiant, et totaux correspondants
+    pub finalized_count: u32,
+    pub present_count: u32,
+    pub late_count: u32,
+    pub absent_count: u32,
+    pub excused_count: u32,
+    // Présences comptées ci-dessus dont l'étudiant est parti avant la fin
+    pub left_early_count: u32,
+    // Émargement du formateur complet (toutes les plages le cas échéant)
+    pub trainer_signed: bool,
+    pub last_finalized_student: Pubkey,
+    // Salle réservée pour la session, `Pubkey::default()` si aucune
+    pub room: Pubkey,
+    // Remplaçant pour cette seule session, `Pubkey::default()` si aucun
+    pub substitute: Pubkey,
+    pub substitute_assigned_by: Pubkey,
+    pub substitute_assigned_at: i64,
+    // Empreinte chaînée des présences clôturées : h = sha256(h || présence sérialisée)
+    pub attendance_hash: [u8; 32],
+    pub finalized_at: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// Série de sessions récurrentes. Les sessions sont créées par lots, au fil des
+// transactions, jusqu'à atteindre `count`.
+#[account]
+pub struct SessionSeries {
+    pub formation: Pubkey,
+    pub trainer: Pubkey,
+    pub title: String,
+    pub description: String,
+    pub first_start_time: i64,
+    pub duration: i64,
+    pub rule: RecurrenceRule,
+    // Jours exclus (horodatages quelconques dans la journée UTC concernée)
+    pub excluded_dates: Vec<i64>,
+    pub count: u16,
+    pub created: u16,
+    // Prochaine occurrence de la récurrence à examiner, jours exclus compris
+    pub next_occurrence: u32,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// Planning d'un formateur, toutes formations confondues, trié par horaire de début.
+// Les sessions terminées sont purgées à chaque ajout.
+#[account]
+pub struct TrainerSchedule {
+    pub trainer: Pubkey,
+    pub entries: Vec<ScheduleEntry>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduleEntry {
+    pub session: Pubkey,
+    pub formation: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+// Réservation de salle vue depuis la formation : la capacité est recopiée pour
+// recalculer le plafond sur place sans relire les comptes des salles
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VenueBooking {
+    pub session: Pubkey,
+    pub room: Pubkey,
+    pub capacity: u8,
+    pub end_time: i64,
+}
+
+pub const VENUE_BOOKING_SPACE: usize = 32 + // session
+    32 + // room
+    1 + // capacity
+    8; // end_time
+
+// Salle gérée par l'administrateur. Les réservations, stockées dans le compte
+// de la salle, sont sérialisées par le runtime : deux sessions ne peuvent pas
+// réserver le même créneau.
+#[account]
+pub struct Room {
+    pub name: String,
+    pub capacity: u8,
+    // Combinaison des drapeaux `EQUIPMENT_*`
+    pub equipment: u16,
+    pub active: bool,
+    pub bookings: Vec<ScheduleEntry>,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -114,6 +373,9 @@ pub struct Enrollment {
     pub student: Pubkey,
     pub status: EnrollmentStatus,
     pub position: u8,
+    // Génération de la position lors de l'attribution
+    pub position_generation: u32,
+    pub modality: Modality,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -124,22 +386,286 @@ pub struct Attendance {
     pub student: Pubkey,
     pub status: AttendanceStatus,
     pub check_in_time: i64,
+    // 0 tant que l'étudiant n'a pas signé sa sortie
+    pub check_out_time: i64,
+    // Temps de présence effectif, borné aux horaires de la session
+    pub attended_seconds: i64,
+    // Sortie signée avant la fin de la session, sans changer le statut de pointage
+    pub left_early: bool,
+    // Heure de signature de chaque plage de la session, 0 si non signée
+    pub segment_check_ins: [i64; MAX_SESSION_SEGMENTS],
+    // Modalité de l'inscription au moment du pointage
+    pub modality: Modality,
+    // Contestation ouverte par l'étudiant, avec l'empreinte de ses justificatifs
+    pub dispute_status: DisputeStatus,
+    pub evidence_hash: [u8; 32],
+    pub disputed_at: i64,
+    // Contestations et justificatifs déposés, bornés par `MAX_CORRECTION_REQUESTS`
+    pub correction_requests: u8,
+    // Justificatif d'absence : empreinte du document et son emplacement hors chaîne
+    pub justification_hash: [u8; 32],
+    pub justification_uri: String,
+    pub justification_status: JustificationStatus,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// Émargement du formateur pour une session
+#[account]
+pub struct TrainerAttendance {
+    pub session: Pubkey,
+    // Formateur de la première signature
+    pub trainer: Pubkey,
+    // Première signature, 0 tant que le formateur n'a pas signé
+    pub signed_at: i64,
+    // Heure de signature de chaque plage de la session, 0 si non signée
+    pub segment_signed_at: [i64; MAX_SESSION_SEGMENTS],
+    // Signataire de chaque plage : titulaire ou remplaçant selon la plage
+    pub segment_signers: [Pubkey; MAX_SESSION_SEGMENTS],
     pub created_at: i64,
     pub updated_at: i64,
 }
 
+// Registre zero-copy des présences d'une session, indexé par `Enrollment.position`.
+// Alternative aux comptes Attendance pour les grandes promotions : un seul compte
+// à créer et à lire pour toute la session.
+#[account(zero_copy)]
+pub struct SessionRoster {
+    pub session: Pubkey,
+    // Heure de pointage par position, 0 si non pointé ou absent
+    pub check_in_times: [i64; ROSTER_SIZE],
+    // Génération de la position lors de l'enregistrement : le statut d'un
+    // étudiant désisté n'est pas attribué au nouvel occupant de sa position
+    pub generations: [u32; ROSTER_SIZE],
+    // Statut par position : 0 si non enregistré, sinon `AttendanceStatus` + 1
+    pub statuses: [u8; ROSTER_SIZE],
+    pub recorded_count: u16,
+    pub _reserved: [u8; 2],
+}
+
+// Accumulateur de Merkle incrémental des présences d'une formation. Seuls la
+// racine, le nombre de feuilles et la frontière nécessaire aux ajouts sont
+// conservés ; le contenu des feuilles est publié dans les événements.
+#[account]
+pub struct AttendanceAccumulator {
+    pub formation: Pubkey,
+    pub root: [u8; 32],
+    pub leaf_count: u64,
+    // Dernier nœud gauche complet de chaque niveau
+    pub frontier: [[u8; 32]; MERKLE_DEPTH],
+}
+
 #[account]
 pub struct WaitlistEntry {
     pub formation: Pubkey,
     pub student: Pubkey,
     pub position: u8,
     pub status: WaitlistStatus,
+    pub tier: WaitlistTier,
+    pub timestamp: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// File d'attente d'une formation, triée par priorité puis par date d'inscription :
+// la tête reçoit la prochaine place libérée
+#[account]
+pub struct WaitlistQueue {
+    pub formation: Pubkey,
+    pub slots: Vec<WaitlistSlot>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct WaitlistSlot {
+    pub student: Pubkey,
+    pub tier: WaitlistTier,
+    pub joined_at: i64,
+}
+
+// Journal des corrections de la présence d'un étudiant à une session. Indépendant
+// de la session, il reste modifiable après la clôture de la feuille d'émargement.
+#[account]
+pub struct CorrectionLog {
+    pub session: Pubkey,
+    pub student: Pubkey,
+    pub entries: Vec<CorrectionEntry>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct CorrectionEntry {
+    pub student: Pubkey,
+    pub old_status: AttendanceStatus,
+    pub new_status: AttendanceStatus,
+    pub actor: Pubkey,
+    pub reason: String,
     pub timestamp: i64,
+}
+
+// Tirage au sort d'une formation sursouscrite. Le formateur s'engage sur
+// `commitment = sha256(secret)` à l'ouverture. À la clôture des inscriptions,
+// le slot de référence `target_slot` est fixé, puis son hachage est relevé dès
+// qu'il est produit : le formateur ne choisit donc pas le slot utilisé. Il révèle
+// ensuite `secret` avant `reveal_deadline`, faute de quoi n'importe qui peut
+// tirer avec le seul hachage du slot. La graine `sha256(secret || slot_hash || formation)`
+// et la liste des participants, conservées dans le compte, suffisent à
+// recalculer le classement avec `Lottery::compute_ranking`.
+#[account]
+pub struct Lottery {
+    pub formation: Pubkey,
+    pub commitment: [u8; 32],
+    pub registration_end: i64,
+    // Slot dont le hachage alimente la graine, 0 tant que les inscriptions ne sont pas closes
+    pub target_slot: u64,
+    // 0 tant que le hachage du slot de référence n'est pas relevé
+    pub reveal_deadline: i64,
+    // Slot de repli fixé après `reveal_deadline` si le secret n'est pas révélé,
+    // 0 sinon ; son hachage remplace le secret du formateur
+    pub fallback_slot: u64,
+    // Participants dans l'ordre d'inscription
+    pub entrants: Vec<Pubkey>,
+    // Indices dans `entrants`, du premier au dernier tiré
+    pub ranking: Vec<u8>,
+    // Les `seats` premiers tirés obtiennent une place, les suivants la liste d'attente
+    pub seats: u8,
+    pub claimed_count: u8,
+    pub claimed: [u8; 32],
+    pub seats_released: bool,
+    pub drawn: bool,
+    // Faux si le tirage a été effectué sans le secret du formateur : `secret`
+    // est alors le hachage du slot de repli
+    pub revealed: bool,
+    pub secret: [u8; 32],
+    pub slot: u64,
+    pub slot_hash: [u8; 32],
+    pub seed: [u8; 32],
+    pub drawn_at: i64,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
 impl Formation {
+    pub fn supports_modality(&self, modality: Modality) -> bool {
+        match self.formation_type {
+            FormationType::Online => modality == Modality::Remote,
+            FormationType::InPerson => modality == Modality::OnSite,
+            FormationType::Hybrid => true,
+        }
+    }
+
+    // Occupe une place dans la modalité choisie ; la capacité globale
+    // `max_students` reste contrôlée par l'appelant
+    pub fn add_to_modality(&mut self, modality: Modality, now: i64) -> Result<()> {
+        require!(self.supports_modality(modality), AlyraError::InvalidModality);
+        let venue_capacity = self.venue_capacity(now);
+        let (current, capacity) = match modality {
+            Modality::OnSite => (&mut self.current_onsite, self.onsite_capacity.min(venue_capacity)),
+            Modality::Remote => (&mut self.current_remote, self.remote_capacity),
+        };
+        require!(*current < capacity, AlyraError::ModalityFull);
+        *current += 1;
+        Ok(())
+    }
+
+    // Plafond des inscrits sur place : la plus petite salle encore réservée, sans
+    // limite quand aucune session à venir n'a de salle
+    pub fn venue_capacity(&self, now: i64) -> u8 {
+        self.venue_bookings.iter()
+            .filter(|booking| booking.session != Pubkey::default() && booking.end_time >= now)
+            .map(|booking| booking.capacity)
+            .min()
+            .unwrap_or(u8::MAX)
+    }
+
+    // Un emplacement libre ou dont la session est terminée accueille la réservation
+    pub fn add_venue_booking(&mut self, booking: VenueBooking, now: i64) -> Result<()> {
+        let slot = self.venue_bookings.iter_mut()
+            .find(|slot| slot.session == Pubkey::default() || slot.end_time < now)
+            .ok_or(AlyraError::VenueBookingsFull)?;
+        *slot = booking;
+        Ok(())
+    }
+
+    pub fn remove_venue_booking(&mut self, session: &Pubkey) {
+        if let Some(slot) = self.venue_bookings.iter_mut().find(|slot| slot.session == *session) {
+            *slot = VenueBooking::default();
+        }
+    }
+
+    pub fn reschedule_venue_booking(&mut self, session: &Pubkey, end_time: i64) {
+        if let Some(slot) = self.venue_bookings.iter_mut().find(|slot| slot.session == *session) {
+            slot.end_time = end_time;
+        }
+    }
+
+    // Répercute la nouvelle capacité d'une salle ; indique si la formation y a
+    // encore une réservation en cours
+    pub fn update_venue_capacity(&mut self, room: &Pubkey, capacity: u8, now: i64) -> bool {
+        let mut booked = false;
+        for slot in self.venue_bookings.iter_mut() {
+            if slot.session != Pubkey::default() && slot.room == *room {
+                slot.capacity = capacity;
+                booked |= slot.end_time >= now;
+            }
+        }
+        booked
+    }
+
+    pub fn remove_from_modality(&mut self, modality: Modality) -> Result<()> {
+        let current = match modality {
+            Modality::OnSite => &mut self.current_onsite,
+            Modality::Remote => &mut self.current_remote,
+        };
+        *current = current.checked_sub(1).ok_or(AlyraError::Underflow)?;
+        Ok(())
+    }
+
+    // Le mode de stockage est figé dès la première présence enregistrée
+    pub fn lock_attendance_storage(&mut self) {
+        self.attendance_recorded = true;
+    }
+
+    pub fn uses_attendance_accounts(&self) -> bool {
+        self.attendance_storage == AttendanceStorage::Accounts
+    }
+
+    pub fn uses_roster(&self) -> bool {
+        self.attendance_storage == AttendanceStorage::Roster
+    }
+
+    pub fn uses_accumulator(&self) -> bool {
+        self.attendance_storage == AttendanceStorage::Accumulator
+    }
+
+    // Attribue la position libre la moins réutilisée, qui reste liée à l'inscrit
+    // par sa génération ; renvoie la position et sa nouvelle génération
+    pub fn assign_position(&mut self) -> Result<(u8, u32)> {
+        let position = (0..ROSTER_SIZE as u8)
+            .filter(|&position| !self.is_position_occupied(position))
+            .min_by_key(|&position| self.position_generations[position as usize])
+            .ok_or(AlyraError::FormationFull)?;
+        let generation = &mut self.position_generations[position as usize];
+        *generation = generation.checked_add(1).ok_or(AlyraError::Overflow)?;
+        let generation = *generation;
+        self.occupied_positions[position as usize / 8] |= 1 << (position % 8);
+        Ok((position, generation))
+    }
+
+    pub fn position_generation(&self, position: u8) -> u32 {
+        self.position_generations.get(position as usize).copied().unwrap_or(0)
+    }
+
+    pub fn is_position_occupied(&self, position: u8) -> bool {
+        let position = position as usize;
+        position < ROSTER_SIZE && self.occupied_positions[position / 8] & (1 << (position % 8)) != 0
+    }
+
+    pub fn free_position(&mut self, position: u8) {
+        let position = position as usize;
+        if position < ROSTER_SIZE {
+            self.occupied_positions[position / 8] &= !(1 << (position % 8));
+        }
+    }
+
     pub fn can_enroll(&self) -> bool {
         self.current_students < self.max_students && self.status == FormationStatus::Active
     }
@@ -150,15 +676,56 @@ impl Formation {
         self.current_students >= self.max_students
     }
 
+    // Les places proposées à la liste d'attente restent réservées jusqu'à la réponse
+    pub fn open_seats(&self) -> u8 {
+        self.max_students
+            .saturating_sub(self.current_students)
+            .saturating_sub(self.pending_promotions)
+    }
+
+    pub fn allows_self_check_in(&self) -> bool {
+        self.attendance_mode != AttendanceMode::RollCall
+    }
+
+    pub fn allows_roll_call(&self) -> bool {
+        self.attendance_mode != AttendanceMode::SelfCheckIn
+    }
+
     pub fn has_available_slots(&self) -> bool {
-        self.current_students < self.max_students
+        self.open_seats() > 0
     }
 
-    pub fn promote_from_waitlist(&mut self) -> Result<()> {
+    pub fn waiting_count(&self) -> u8 {
+        self.current_waitlisted.saturating_sub(self.pending_promotions)
+    }
+
+    pub fn reserve_seat(&mut self) -> Result<()> {
         if !self.has_available_slots() {
             return Err(error!(AlyraError::FormationFull));
         }
-        
+
+        self.pending_promotions = self.pending_promotions.checked_add(1)
+            .ok_or(AlyraError::Overflow)?;
+
+        Ok(())
+    }
+
+    // Offre déclinée ou expirée : la réservation est levée et l'étudiant quitte la liste
+    pub fn release_seat(&mut self) -> Result<()> {
+        self.pending_promotions = self.pending_promotions.checked_sub(1)
+            .ok_or(AlyraError::Underflow)?;
+
+        self.current_waitlisted = self.current_waitlisted.checked_sub(1)
+            .ok_or(AlyraError::Underflow)?;
+
+        Ok(())
+    }
+
+    // Offre acceptée : la place réservée devient une inscription
+    pub fn promote_from_waitlist(&mut self) -> Result<()> {
+        self.pending_promotions = self.pending_promotions.checked_sub(1)
+            .ok_or(AlyraError::Underflow)?;
+
         self.current_students = self.current_students.checked_add(1)
             .ok_or(AlyraError::Overflow)?;
             
@@ -168,6 +735,24 @@ impl Formation {
         Ok(())
     }
 
+    // Place tirée au sort confirmée par son gagnant
+    pub fn claim_reserved_seat(&mut self) -> Result<()> {
+        self.pending_promotions = self.pending_promotions.checked_sub(1)
+            .ok_or(AlyraError::Underflow)?;
+
+        self.current_students = self.current_students.checked_add(1)
+            .ok_or(AlyraError::Overflow)?;
+
+        Ok(())
+    }
+
+    pub fn remove_from_waitlist(&mut self) -> Result<()> {
+        self.current_waitlisted = self.current_waitlisted.checked_sub(1)
+            .ok_or(AlyraError::Underflow)?;
+
+        Ok(())
+    }
+
     pub fn add_to_waitlist(&mut self) -> Result<()> {
         if !self.can_join_waitlist() {
             return Err(error!(AlyraError::WaitlistFull));
@@ -180,94 +765,820 @@ impl Formation {
     }
 }
 
-impl WaitlistEntry {
-    pub fn is_promotable(&self) -> bool {
-        self.status == WaitlistStatus::Waiting
+impl Session {
+    // Session créée hors du contexte Anchor (séries), signée par code du formateur
+    pub fn new(
+        formation: Pubkey,
+        trainer: Pubkey,
+        title: String,
+        description: String,
+        start_time: i64,
+        end_time: i64,
+        now: i64,
+    ) -> Self {
+        Session {
+            formation,
+            trainer,
+            title,
+            description,
+            start_time,
+            end_time,
+            last_code_window: 0,
+            check_in_method: CheckInMethod::TrainerSignature,
+            code_anchor: [0u8; 32],
+            code_anchor_window: 0,
+            code_chain_length: 0,
+            segments: Vec::new(),
+            segment_rule: SegmentRule::AllSegments,
+            status: SessionStatus::Scheduled,
+            attendance_count: 0,
+            recorded_generations: [0u32; ROSTER_SIZE],
+            finalized_count: 0,
+            present_count: 0,
+            late_count: 0,
+            absent_count: 0,
+            excused_count: 0,
+            left_early_count: 0,
+            trainer_signed: false,
+            last_finalized_student: Pubkey::default(),
+            room: Pubkey::default(),
+            substitute: Pubkey::default(),
+            substitute_assigned_by: Pubkey::default(),
+            substitute_assigned_at: 0,
+            attendance_hash: [0u8; 32],
+            finalized_at: 0,
+            created_at: now,
+            updated_at: now,
+        }
     }
 
-    pub fn promote(&mut self) {
-        self.status = WaitlistStatus::PendingPromotion;
+    // Le pointage ouvre peu avant le début et reste possible, en retard, jusqu'à
+    // `SESSION_TIMEOUT` après la fin de la session
+    pub fn check_in_opens_at(&self) -> i64 {
+        self.start_time - CHECK_IN_OPEN_MARGIN
     }
 
-    pub fn accept_promotion(&mut self) {
-        self.status = WaitlistStatus::Promoted;
+    pub fn check_in_closes_at(&self) -> i64 {
+        self.end_time + SESSION_TIMEOUT
     }
 
-    pub fn decline_promotion(&mut self) {
-        self.status = WaitlistStatus::Declined;
+    pub fn attendance_status_at(&self, time: i64, late_threshold: i64) -> Result<AttendanceStatus> {
+        if time < self.check_in_opens_at() {
+            return Err(error!(AlyraError::CheckInNotOpen));
+        }
+        if time > self.check_in_closes_at() {
+            return Err(error!(AlyraError::CheckInClosed));
+        }
+
+        if time <= self.start_time + late_threshold {
+            Ok(AttendanceStatus::Present)
+        } else {
+            Ok(AttendanceStatus::Late)
+        }
     }
 
-    pub fn is_expired(&self, current_time: i64, promotion_timeout: i64) -> bool {
-        self.status == WaitlistStatus::PendingPromotion && 
-        current_time > self.timestamp + promotion_timeout
+    // Plage d'émargement ouverte à `time`, selon la même marge d'ouverture que la session
+    pub fn segment_at(&self, time: i64) -> Option<usize> {
+        self.segments
+            .iter()
+            .position(|segment| time >= segment.start_time - CHECK_IN_OPEN_MARGIN && time <= segment.end_time)
     }
-}
 
-#[derive(Accounts)]
-pub struct RequestAccess<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
-    
-    #[account(
-        init,
-        payer = user,
-        space = ACCESS_REQUEST_SPACE
-    )]
-    pub access_request: Account<'info, AccessRequest>,
-    
-    pub system_program: Program<'info, System>,
-}
+    // Plage ouverte et statut de la signature, le retard étant mesuré depuis le début de la plage
+    pub fn segment_status_at(&self, time: i64, late_threshold: i64) -> Result<(usize, AttendanceStatus)> {
+        let index = self.segment_at(time).ok_or(AlyraError::CheckInNotOpen)?;
+        let status = if time <= self.segments[index].start_time + late_threshold {
+            AttendanceStatus::Present
+        } else {
+            AttendanceStatus::Late
+        };
+        Ok((index, status))
+    }
 
-#[derive(Accounts)]
-pub struct CreateFormation<'info> {
-    #[account(mut)]
-    pub trainer: Signer<'info>,
-    
-    #[account(
-        init,
-        payer = trainer,
-        space = 8 + 32 + 200 + 1000 + 1 + 8 + 8 + 8 + 8 + 8 + 8
-    )]
-    pub formation: Account<'info, Formation>,
-    
-    pub system_program: Program<'info, System>,
-}
+    // Formateur de la session ou remplaçant désigné : appel, codes de pointage,
+    // émargement du formateur et clôture
+    pub fn is_acting_trainer(&self, key: &Pubkey) -> bool {
+        *key == self.trainer || (self.substitute != Pubkey::default() && *key == self.substitute)
+    }
 
-#[derive(Accounts)]
-pub struct CreateSession<'info> {
-    #[account(mut)]
-    pub trainer: Signer<'info>,
-    
-    #[account(mut)]
-    pub formation: Account<'info, Formation>,
-    
-    #[account(
-        init,
-        payer = trainer,
-        space = 8 + 32 + 32 + 200 + 1000 + 8 + 8 + 8 + 8
-    )]
-    pub session: Account<'info, Session>,
-    
-    pub system_program: Program<'info, System>,
-}
+    // Compte Attendance créé pour cet inscrit
+    pub fn add_attendance(&mut self, enrollment: &Enrollment) -> Result<()> {
+        self.mark_recorded(enrollment);
+        self.attendance_count = self.attendance_count.checked_add(1)
+            .ok_or(AlyraError::Overflow)?;
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct EnrollInFormation<'info> {
-    #[account(mut)]
-    pub student: Signer<'info>,
-    
-    #[account(mut)]
-    pub formation: Account<'info, Formation>,
-    
-    #[account(
-        init,
-        payer = student,
-        space = 8 + 32 + 32 + 1 + 8 + 8 + 8
-    )]
-    pub enrollment: Account<'info, Enrollment>,
-    
-    pub system_program: Program<'info, System>,
-}
+    pub fn is_recorded(&self, enrollment: &Enrollment) -> bool {
+        matches!(
+            self.recorded_generations.get(enrollment.position as usize),
+            Some(&generation) if generation == enrollment.position_generation
+        )
+    }
+
+    pub fn mark_recorded(&mut self, enrollment: &Enrollment) {
+        if let Some(generation) = self.recorded_generations.get_mut(enrollment.position as usize) {
+            *generation = enrollment.position_generation;
+        }
+    }
+
+    // Feuille ajoutée à l'accumulateur pour cet inscrit : sans compte à relire,
+    // les totaux et l'empreinte chaînée de la session sont tenus au fil des ajouts
+    pub fn add_leaf(&mut self, enrollment: &Enrollment, status: AttendanceStatus, leaf: &[u8; 32]) -> Result<()> {
+        self.mark_recorded(enrollment);
+        let count = match status {
+            AttendanceStatus::Present => &mut self.present_count,
+            AttendanceStatus::Late => &mut self.late_count,
+            AttendanceStatus::Absent => &mut self.absent_count,
+            AttendanceStatus::Excused => &mut self.excused_count,
+        };
+        Self::adjust(count, true)?;
+        self.attendance_hash = hashv(&[&self.attendance_hash, leaf]).to_bytes();
+        Ok(())
+    }
+
+    // Chaque inscrit de la formation a une présence (pointage, appel ou absence
+    // marquée par les cranks `mark_absent` et `mark_absent_leaves`) ; celle d'un
+    // étudiant désisté depuis ne couvre pas le nouvel occupant de sa position
+    pub fn covers_enrolled(&self, formation: &Formation) -> bool {
+        (0..ROSTER_SIZE as u8)
+            .filter(|&position| formation.is_position_occupied(position))
+            .all(|position| {
+                self.recorded_generations[position as usize] == formation.position_generation(position)
+            })
+    }
+
+    pub fn is_open_for_attendance(&self) -> bool {
+        self.status == SessionStatus::Scheduled
+    }
+
+    // Ajoute une présence à la clôture en cours ; les présences doivent arriver
+    // triées par étudiant pour que l'empreinte soit reproductible hors chaîne
+    pub fn finalize_attendance(&mut self, attendance: &Attendance) -> Result<()> {
+        require!(
+            attendance.student > self.last_finalized_student,
+            AlyraError::InvalidAttendanceAccounts
+        );
+
+        self.tally(attendance, true)?;
+        self.attendance_hash = hashv(&[&self.attendance_hash, &attendance.try_to_vec()?]).to_bytes();
+        self.last_finalized_student = attendance.student;
+        self.finalized_count = self.finalized_count.checked_add(1).ok_or(AlyraError::Overflow)?;
+        Ok(())
+    }
+
+    // Une présence déjà comptée lors de la clôture
+    pub fn is_finalized(&self, attendance: &Attendance) -> bool {
+        matches!(self.status, SessionStatus::Finalizing | SessionStatus::Finalized)
+            && attendance.student <= self.last_finalized_student
+    }
+
+    // Ajoute (ou retire) une présence des totaux de la feuille d'émargement ; un
+    // départ anticipé est compté à part, en plus du statut de pointage
+    pub fn tally(&mut self, attendance: &Attendance, add: bool) -> Result<()> {
+        let attended = self.is_attended(attendance);
+        let count = if attendance.status == AttendanceStatus::Excused {
+            &mut self.excused_count
+        } else if !attended {
+            &mut self.absent_count
+        } else if attendance.status == AttendanceStatus::Late {
+            &mut self.late_count
+        } else {
+            &mut self.present_count
+        };
+        Self::adjust(count, add)?;
+
+        if attended && attendance.left_early {
+            Self::adjust(&mut self.left_early_count, add)?;
+        }
+        Ok(())
+    }
+
+    fn adjust(count: &mut u32, add: bool) -> Result<()> {
+        *count = if add {
+            count.checked_add(1).ok_or(AlyraError::Overflow)?
+        } else {
+            count.checked_sub(1).ok_or(AlyraError::Underflow)?
+        };
+        Ok(())
+    }
+
+    // Taux de présence en points de base ; les absences excusées sont exclues
+    // du dénominateur au lieu d'être comptées comme des absences
+    pub fn attendance_rate(&self) -> u16 {
+        let attended = self.present_count as u64 + self.late_count as u64;
+        let expected = attended + self.absent_count as u64;
+        if expected == 0 {
+            return 10_000;
+        }
+        (attended * 10_000 / expected) as u16
+    }
+
+    pub fn is_attended(&self, attendance: &Attendance) -> bool {
+        if matches!(attendance.status, AttendanceStatus::Absent | AttendanceStatus::Excused) {
+            return false;
+        }
+        if self.segments.is_empty() {
+            return true;
+        }
+
+        let signed = attendance.segment_check_ins[..self.segments.len()]
+            .iter()
+            .filter(|&&time| time != 0)
+            .count();
+        match self.segment_rule {
+            SegmentRule::AllSegments => signed == self.segments.len(),
+            SegmentRule::AnySegment => signed > 0,
+            SegmentRule::AtLeast(minimum) => signed >= minimum as usize,
+        }
+    }
+
+    // Code affiché par le formateur : domaine || session || fenêtre (i64) || nonce (16 octets)
+    pub fn check_in_code_window(session: &Pubkey, message: &[u8]) -> Option<i64> {
+        let prefix_len = CHECK_IN_CODE_DOMAIN.len();
+        if message.len() != prefix_len + 32 + 8 + 16 || !message.starts_with(CHECK_IN_CODE_DOMAIN) {
+            return None;
+        }
+        if &message[prefix_len..prefix_len + 32] != session.as_ref() {
+            return None;
+        }
+
+        let mut window = [0u8; 8];
+        window.copy_from_slice(&message[prefix_len + 32..prefix_len + 40]);
+        Some(i64::from_le_bytes(window))
+    }
+
+    // Pointage signé hors ligne par l'étudiant : domaine || session || étudiant || horodatage (i64)
+    pub fn offline_check_in(session: &Pubkey, message: &[u8]) -> Option<(Pubkey, i64)> {
+        let prefix_len = OFFLINE_CHECK_IN_DOMAIN.len();
+        if message.len() != prefix_len + 32 + 32 + 8 || !message.starts_with(OFFLINE_CHECK_IN_DOMAIN) {
+            return None;
+        }
+        if &message[prefix_len..prefix_len + 32] != session.as_ref() {
+            return None;
+        }
+
+        let student = Pubkey::try_from(&message[prefix_len + 32..prefix_len + 64]).ok()?;
+        let mut timestamp = [0u8; 8];
+        timestamp.copy_from_slice(&message[prefix_len + 64..prefix_len + 72]);
+        Some((student, i64::from_le_bytes(timestamp)))
+    }
+
+    // La fenêtre courante et la précédente sont acceptées pour absorber le délai
+    // d'affichage du QR code, sans jamais revenir avant la dernière fenêtre utilisée
+    pub fn accept_check_in_window(&mut self, window: i64, now: i64) -> Result<()> {
+        let current_window = now / CHECK_IN_CODE_WINDOW;
+        if window > current_window || window < current_window - 1 || window < self.last_code_window {
+            return Err(error!(AlyraError::InvalidCheckInCode));
+        }
+
+        self.last_code_window = window;
+        Ok(())
+    }
+
+    pub fn code_window_at(&self, now: i64) -> i64 {
+        (now - self.check_in_opens_at()) / CHECK_IN_CODE_WINDOW
+    }
+
+    // Le code de la fenêtre courante, haché autant de fois qu'il y a de fenêtres
+    // depuis le dernier maillon connu, doit redonner ce maillon. Un code divulgué
+    // devient inutilisable dès que sa fenêtre est passée.
+    pub fn accept_chain_code(&mut self, code: [u8; 32], now: i64) -> Result<()> {
+        self.accept_chain_link(code, self.code_window_at(now))
+    }
+
+    // Chaque maillon accepté devient le nouveau point de départ, ce qui borne le
+    // nombre de hachages à `MAX_CODE_CHAIN_STEPS` ; au-delà, le formateur avance
+    // la chaîne avec `advance_code_chain`
+    pub fn accept_chain_link(&mut self, link: [u8; 32], window: i64) -> Result<()> {
+        if window < self.code_anchor_window || window >= self.code_chain_length as i64 {
+            return Err(error!(AlyraError::InvalidCheckInCode));
+        }
+        if window - self.code_anchor_window > MAX_CODE_CHAIN_STEPS {
+            return Err(error!(AlyraError::CodeChainGapTooLarge));
+        }
+
+        let mut digest = link;
+        for _ in self.code_anchor_window..window {
+            digest = hash(&digest).to_bytes();
+        }
+        if digest != self.code_anchor {
+            return Err(error!(AlyraError::InvalidCheckInCode));
+        }
+
+        self.code_anchor = link;
+        self.code_anchor_window = window;
+        Ok(())
+    }
+}
+
+impl ScheduleEntry {
+    // Ajoute un créneau à une liste triée par début, après avoir purgé les
+    // créneaux terminés ; `conflict` et `full` sont les erreurs de l'appelant
+    fn insert_into(
+        self,
+        entries: &mut Vec<ScheduleEntry>,
+        capacity: usize,
+        now: i64,
+        conflict: AlyraError,
+        full: AlyraError,
+    ) -> Result<()> {
+        entries.retain(|existing| existing.end_time >= now);
+
+        // Deux créneaux se chevauchent si chacun commence avant la fin de l'autre
+        if entries.iter().any(|existing| {
+            existing.start_time < self.end_time && self.start_time < existing.end_time
+        }) {
+            return Err(conflict.into());
+        }
+        if entries.len() >= capacity {
+            return Err(full.into());
+        }
+
+        let index = entries.partition_point(|existing| existing.start_time < self.start_time);
+        entries.insert(index, self);
+        Ok(())
+    }
+
+    fn remove_from(entries: &mut Vec<ScheduleEntry>, session: &Pubkey) -> Option<ScheduleEntry> {
+        let index = entries.iter().position(|entry| entry.session == *session)?;
+        Some(entries.remove(index))
+    }
+}
+
+impl TrainerSchedule {
+    pub fn insert(&mut self, entry: ScheduleEntry, now: i64) -> Result<()> {
+        entry.insert_into(
+            &mut self.entries,
+            MAX_SCHEDULE_ENTRIES,
+            now,
+            AlyraError::ScheduleConflict,
+            AlyraError::ScheduleFull,
+        )
+    }
+
+    pub fn remove(&mut self, session: &Pubkey) -> Option<ScheduleEntry> {
+        ScheduleEntry::remove_from(&mut self.entries, session)
+    }
+}
+
+impl Room {
+    pub fn has_equipment(&self, required: u16) -> bool {
+        self.equipment & required == required
+    }
+
+    pub fn book(&mut self, entry: ScheduleEntry, now: i64) -> Result<()> {
+        require!(self.active, AlyraError::RoomUnsuitable);
+        entry.insert_into(
+            &mut self.bookings,
+            MAX_ROOM_BOOKINGS,
+            now,
+            AlyraError::RoomConflict,
+            AlyraError::RoomFull,
+        )
+    }
+
+    pub fn release(&mut self, session: &Pubkey) -> Option<ScheduleEntry> {
+        ScheduleEntry::remove_from(&mut self.bookings, session)
+    }
+}
+
+impl SessionSeries {
+    fn period(&self) -> i64 {
+        match self.rule {
+            RecurrenceRule::Daily => SECONDS_PER_DAY,
+            RecurrenceRule::Weekly => 7 * SECONDS_PER_DAY,
+            RecurrenceRule::EveryNDays(days) => days as i64 * SECONDS_PER_DAY,
+        }
+    }
+
+    fn is_excluded(&self, start_time: i64) -> bool {
+        let day = start_time.div_euclid(SECONDS_PER_DAY);
+        self.excluded_dates
+            .iter()
+            .any(|date| date.div_euclid(SECONDS_PER_DAY) == day)
+    }
+
+    // Horaire de la prochaine session de la série, en sautant les jours exclus.
+    // Chaque jour exclu écarte au plus une occurrence, la boucle est donc bornée.
+    pub fn next_start_time(&mut self) -> Result<i64> {
+        loop {
+            let start_time = (self.next_occurrence as i64)
+                .checked_mul(self.period())
+                .and_then(|offset| offset.checked_add(self.first_start_time))
+                .ok_or(AlyraError::Overflow)?;
+            self.next_occurrence = self.next_occurrence.checked_add(1).ok_or(AlyraError::Overflow)?;
+            if !self.is_excluded(start_time) {
+                return Ok(start_time);
+            }
+        }
+    }
+}
+
+impl Attendance {
+    // Présence créée hors du contexte Anchor (appel, crank, lot hors ligne)
+    pub fn new(enrollment: &Enrollment, session: Pubkey, status: AttendanceStatus, check_in_time: i64, now: i64) -> Self {
+        Attendance {
+            session,
+            student: enrollment.student,
+            status,
+            check_in_time,
+            check_out_time: 0,
+            attended_seconds: 0,
+            left_early: false,
+            segment_check_ins: [0; MAX_SESSION_SEGMENTS],
+            modality: enrollment.modality,
+            dispute_status: DisputeStatus::None,
+            evidence_hash: [0u8; 32],
+            disputed_at: 0,
+            correction_requests: 0,
+            justification_hash: [0u8; 32],
+            justification_uri: String::new(),
+            justification_status: JustificationStatus::None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    // Nouvelle contestation ou nouveau justificatif de l'étudiant
+    pub fn request_correction(&mut self) -> Result<()> {
+        require!(
+            (self.correction_requests as usize) < MAX_CORRECTION_REQUESTS,
+            AlyraError::CorrectionLimitReached
+        );
+        self.correction_requests += 1;
+        Ok(())
+    }
+
+    // Une plage déjà signée ne peut l'être une seconde fois ; un retard sur une plage
+    // rend la présence globale en retard
+    pub fn sign_segment(&mut self, index: usize, status: AttendanceStatus, now: i64) -> Result<()> {
+        require!(self.check_out_time == 0, AlyraError::InvalidAttendanceStatus);
+        require!(self.segment_check_ins[index] == 0, AlyraError::AlreadyCheckedIn);
+
+        self.segment_check_ins[index] = now;
+        if self.status == AttendanceStatus::Absent || status == AttendanceStatus::Late {
+            self.status = status;
+        }
+        if self.check_in_time == 0 {
+            self.check_in_time = now;
+        }
+        self.updated_at = now;
+        Ok(())
+    }
+
+    pub fn check_out(&mut self, session: &Session, now: i64) -> Result<()> {
+        let checked_in = matches!(self.status, AttendanceStatus::Present | AttendanceStatus::Late);
+        if !checked_in || self.check_out_time != 0 {
+            return Err(error!(AlyraError::InvalidAttendanceStatus));
+        }
+
+        let from = self.check_in_time.max(session.start_time);
+        let until = now.min(session.end_time);
+        self.attended_seconds = (until - from).max(0);
+        self.check_out_time = now;
+
+        self.left_early = now < session.end_time - EARLY_DEPARTURE_TOLERANCE;
+        self.updated_at = now;
+        Ok(())
+    }
+}
+
+impl TrainerAttendance {
+    // Une signature par session, ou une par plage si la session est découpée
+    pub fn sign(&mut self, session: &Session, trainer: Pubkey, now: i64) -> Result<()> {
+        if session.segments.is_empty() {
+            require!(self.signed_at == 0, AlyraError::AlreadyCheckedIn);
+        } else {
+            let index = session.segment_at(now).ok_or(AlyraError::CheckInNotOpen)?;
+            require!(self.segment_signed_at[index] == 0, AlyraError::AlreadyCheckedIn);
+            self.segment_signed_at[index] = now;
+            self.segment_signers[index] = trainer;
+        }
+
+        if self.signed_at == 0 {
+            self.signed_at = now;
+            self.trainer = trainer;
+        }
+        self.updated_at = now;
+        Ok(())
+    }
+
+    pub fn is_complete(&self, session: &Session) -> bool {
+        self.signed_at != 0
+            && self.segment_signed_at[..session.segments.len()].iter().all(|&time| time != 0)
+    }
+}
+
+impl SessionRoster {
+    fn encode(status: AttendanceStatus) -> u8 {
+        match status {
+            AttendanceStatus::Present => 1,
+            AttendanceStatus::Absent => 2,
+            AttendanceStatus::Late => 3,
+            // 4 : ancien statut de départ anticipé, laissé inutilisé
+            AttendanceStatus::Excused => 5,
+        }
+    }
+
+    // Statut enregistré à cette position et génération de la position à ce moment
+    pub fn entry(&self, position: u8) -> Option<(AttendanceStatus, u32)> {
+        let status = match self.statuses.get(position as usize)? {
+            1 => AttendanceStatus::Present,
+            2 => AttendanceStatus::Absent,
+            3 => AttendanceStatus::Late,
+            5 => AttendanceStatus::Excused,
+            _ => return None,
+        };
+        Some((status, self.generations[position as usize]))
+    }
+
+    // Statut de l'inscrit, `None` tant qu'il n'a pas été enregistré
+    pub fn status(&self, enrollment: &Enrollment) -> Option<AttendanceStatus> {
+        match self.entry(enrollment.position)? {
+            (status, generation) if generation == enrollment.position_generation => Some(status),
+            _ => None,
+        }
+    }
+
+    // Un statut laissé par un étudiant désisté depuis est remplacé
+    pub fn record(&mut self, enrollment: &Enrollment, status: AttendanceStatus, check_in_time: i64) -> Result<()> {
+        let index = enrollment.position as usize;
+        require!(index < ROSTER_SIZE, AlyraError::InvalidAttendanceAccounts);
+
+        if self.statuses[index] == 0 {
+            self.recorded_count = self.recorded_count.checked_add(1).ok_or(AlyraError::Overflow)?;
+        }
+        self.statuses[index] = Self::encode(status);
+        self.generations[index] = enrollment.position_generation;
+        self.check_in_times[index] = check_in_time;
+        Ok(())
+    }
+}
+
+impl AttendanceAccumulator {
+    pub fn append(&mut self, leaf: [u8; 32]) -> Result<()> {
+        require!(self.leaf_count < 1u64 << MERKLE_DEPTH, AlyraError::AccumulatorFull);
+
+        let mut index = self.leaf_count;
+        let mut node = leaf;
+        let mut zero = [0u8; 32];
+        for level in 0..MERKLE_DEPTH {
+            node = if index & 1 == 0 {
+                self.frontier[level] = node;
+                merkle::hash_node(&node, &zero)
+            } else {
+                merkle::hash_node(&self.frontier[level], &node)
+            };
+            zero = merkle::hash_node(&zero, &zero);
+            index /= 2;
+        }
+
+        self.root = node;
+        self.leaf_count += 1;
+        Ok(())
+    }
+}
+
+// Cycle de vie d'une entrée de liste d'attente :
+//
+//   Waiting ──offre──> PendingPromotion ──acceptation──> Promoted
+//      │                      ├──refus──> Declined
+//      │                      └──délai dépassé──> Expired
+//      └──désistement──> Withdrawn
+//
+// Toute autre transition est refusée.
+impl WaitlistEntry {
+    pub fn is_promotable(&self) -> bool {
+        self.status == WaitlistStatus::Waiting
+    }
+
+    fn transition(&mut self, next: WaitlistStatus, now: i64) -> Result<()> {
+        let allowed = matches!(
+            (self.status, next),
+            (WaitlistStatus::Waiting, WaitlistStatus::PendingPromotion)
+                | (WaitlistStatus::Waiting, WaitlistStatus::Withdrawn)
+                | (WaitlistStatus::PendingPromotion, WaitlistStatus::Promoted)
+                | (WaitlistStatus::PendingPromotion, WaitlistStatus::Declined)
+                | (WaitlistStatus::PendingPromotion, WaitlistStatus::Expired)
+        );
+        if !allowed {
+            return Err(error!(AlyraError::InvalidWaitlistStatus));
+        }
+
+        self.status = next;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    pub fn promote(&mut self, offered_at: i64) -> Result<()> {
+        self.transition(WaitlistStatus::PendingPromotion, offered_at)?;
+        self.timestamp = offered_at;
+        Ok(())
+    }
+
+    pub fn accept_promotion(&mut self, now: i64, promotion_timeout: i64) -> Result<()> {
+        if self.is_expired(now, promotion_timeout) {
+            return Err(error!(AlyraError::PromotionExpired));
+        }
+        self.transition(WaitlistStatus::Promoted, now)
+    }
+
+    pub fn decline_promotion(&mut self, now: i64) -> Result<()> {
+        self.transition(WaitlistStatus::Declined, now)
+    }
+
+    pub fn expire(&mut self, now: i64, promotion_timeout: i64) -> Result<()> {
+        if !self.is_expired(now, promotion_timeout) {
+            return Err(error!(AlyraError::PromotionNotExpired));
+        }
+        self.transition(WaitlistStatus::Expired, now)
+    }
+
+    pub fn withdraw(&mut self, now: i64) -> Result<()> {
+        self.transition(WaitlistStatus::Withdrawn, now)
+    }
+
+    pub fn is_expired(&self, current_time: i64, promotion_timeout: i64) -> bool {
+        self.status == WaitlistStatus::PendingPromotion && 
+        current_time > self.timestamp + promotion_timeout
+    }
+}
+
+impl WaitlistQueue {
+    pub fn head(&self) -> Option<Pubkey> {
+        self.slots.first().map(|slot| slot.student)
+    }
+
+    // Insère après toutes les entrées de priorité supérieure ou égale inscrites plus tôt
+    pub fn insert(&mut self, slot: WaitlistSlot) -> Result<()> {
+        if self.slots.len() >= MAX_WAITLIST_SIZE as usize {
+            return Err(error!(AlyraError::WaitlistFull));
+        }
+
+        let index = self.slots
+            .iter()
+            .position(|s| (s.tier, s.joined_at) > (slot.tier, slot.joined_at))
+            .unwrap_or(self.slots.len());
+        self.slots.insert(index, slot);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, student: &Pubkey) -> Option<WaitlistSlot> {
+        self.slots
+            .iter()
+            .position(|s| s.student == *student)
+            .map(|index| self.slots.remove(index))
+    }
+}
+
+impl CorrectionLog {
+    pub fn append(&mut self, entry: CorrectionEntry) -> Result<()> {
+        require!(
+            entry.reason.len() <= MAX_CORRECTION_REASON_LENGTH,
+            AlyraError::ReasonTooLong
+        );
+        require!(
+            self.entries.len() < MAX_CORRECTION_REQUESTS,
+            AlyraError::CorrectionLogFull
+        );
+        self.entries.push(entry);
+        Ok(())
+    }
+}
+
+impl Lottery {
+    pub fn entrant_index(&self, student: &Pubkey) -> Option<usize> {
+        self.entrants.iter().position(|entrant| entrant == student)
+    }
+
+    // Rang de tirage d'un participant (0 = premier tiré)
+    pub fn rank_of(&self, student: &Pubkey) -> Option<usize> {
+        let index = self.entrant_index(student)?;
+        self.ranking.iter().position(|&i| i as usize == index)
+    }
+
+    pub fn is_claimed(&self, index: usize) -> bool {
+        self.claimed[index / 8] & (1 << (index % 8)) != 0
+    }
+
+    pub fn mark_claimed(&mut self, index: usize) -> Result<()> {
+        self.claimed[index / 8] |= 1 << (index % 8);
+        self.claimed_count = self.claimed_count.checked_add(1)
+            .ok_or(AlyraError::Overflow)?;
+        Ok(())
+    }
+
+    // Hachage du plus petit slot >= `target_slot` présent dans les données du sysvar
+    // SlotHashes (slots décroissants). Le slot de référence a pu ne produire aucun
+    // bloc : le premier slot suivant est alors retenu, à condition que le sysvar
+    // remonte jusqu'à `target_slot` pour que ce choix soit sans ambiguïté.
+    pub fn find_slot_hash(slot_hashes: &[u8], target_slot: u64) -> Option<(u64, [u8; 32])> {
+        let count = u64::from_le_bytes(slot_hashes.get(..8)?.try_into().ok()?) as usize;
+        let entries = slot_hashes.get(8..8 + count.checked_mul(40)?)?;
+
+        let mut found = None;
+        let mut covered = false;
+        for entry in entries.chunks_exact(40) {
+            let slot = u64::from_le_bytes(entry[..8].try_into().ok()?);
+            if slot < target_slot {
+                covered = true;
+                break;
+            }
+            let mut hash = [0u8; 32];
+            hash.copy_from_slice(&entry[8..]);
+            found = Some((slot, hash));
+            if slot == target_slot {
+                covered = true;
+                break;
+            }
+        }
+        if covered { found } else { None }
+    }
+
+    pub fn compute_seed(secret: &[u8; 32], slot_hash: &[u8; 32], formation: &Pubkey) -> [u8; 32] {
+        hashv(&[secret, slot_hash, formation.as_ref()]).to_bytes()
+    }
+
+    // Mélange de Fisher-Yates déterministe, tirant chaque indice de sha256(seed || i)
+    pub fn compute_ranking(seed: &[u8; 32], count: usize) -> Vec<u8> {
+        let mut ranking: Vec<u8> = (0..count).map(|i| i as u8).collect();
+        for i in (1..count).rev() {
+            let digest = hashv(&[seed, &(i as u64).to_le_bytes()]).to_bytes();
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&digest[..8]);
+            let j = (u64::from_le_bytes(bytes) % (i as u64 + 1)) as usize;
+            ranking.swap(i, j);
+        }
+        ranking
+    }
+}
+
+#[derive(Accounts)]
+pub struct RequestAccess<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = user,
+        space = ACCESS_REQUEST_SPACE
+    )]
+    pub access_request: Account<'info, AccessRequest>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateFormation<'info> {
+    #[account(mut)]
+    pub trainer: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = trainer,
+        space = 8 + 32 + 200 + 1000 + 1 + 8 + 8 + 8 + 8 + 8 + 8
+    )]
+    pub formation: Account<'info, Formation>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateSession<'info> {
+    #[account(mut)]
+    pub trainer: Signer<'info>,
+    
+    #[account(mut)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        init,
+        payer = trainer,
+        space = 8 + 32 + 32 + 200 + 1000 + 8 + 8 + 8 + 8
+    )]
+    pub session: Account<'info, Session>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EnrollInFormation<'info> {
+    #[account(mut)]
+    pub student: Signer<'info>,
+    
+    #[account(mut)]
+    pub formation: Account<'info, Formation>,
+    
+    #[account(
+        init,
+        payer = student,
+        space = 8 + 32 + 32 + 1 + 8 + 8 + 8
+    )]
+    pub enrollment: Account<'info, Enrollment>,
+    
+    pub system_program: Program<'info, System>,
+}
 
 #[derive(Accounts)]
 pub struct RecordAttendance<'info> {
@@ -288,4 +1599,566 @@ pub struct RecordAttendance<'info> {
     pub attendance: Account<'info, Attendance>,
     
     pub system_program: Program<'info, System>,
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle::AttendanceLeaf;
+
+    const TIMEOUT: i64 = 3_600;
+
+    fn entry(status: WaitlistStatus) -> WaitlistEntry {
+        WaitlistEntry {
+            formation: Pubkey::new_unique(),
+            student: Pubkey::new_unique(),
+            position: 0,
+            status,
+            tier: WaitlistTier::General,
+            timestamp: 0,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn slot(tier: WaitlistTier, joined_at: i64) -> WaitlistSlot {
+        WaitlistSlot { student: Pubkey::new_unique(), tier, joined_at }
+    }
+
+    // Données du sysvar SlotHashes : nombre d'entrées puis (slot, hachage), slots décroissants
+    fn slot_hashes(slots: &[u64]) -> Vec<u8> {
+        let mut data = (slots.len() as u64).to_le_bytes().to_vec();
+        for &slot in slots {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&[slot as u8; 32]);
+        }
+        data
+    }
+
+    #[test]
+    fn lottery_uses_first_slot_from_target() {
+        let data = slot_hashes(&[105, 103, 100, 98]);
+        assert_eq!(Lottery::find_slot_hash(&data, 100), Some((100, [100; 32])));
+        // Slot de référence sans bloc : le suivant est retenu
+        assert_eq!(Lottery::find_slot_hash(&data, 101), Some((103, [103; 32])));
+        assert_eq!(Lottery::find_slot_hash(&data, 106), None);
+    }
+
+    #[test]
+    fn lottery_refuses_target_older_than_slot_hashes() {
+        let data = slot_hashes(&[105, 103]);
+        assert_eq!(Lottery::find_slot_hash(&data, 101), None);
+        assert_eq!(Lottery::find_slot_hash(&data, 103), Some((103, [103; 32])));
+    }
+
+    #[test]
+    fn offer_then_accept_within_timeout() {
+        let mut entry = entry(WaitlistStatus::Waiting);
+        entry.promote(100).unwrap();
+        assert!(entry.status == WaitlistStatus::PendingPromotion);
+        assert_eq!(entry.timestamp, 100);
+
+        entry.accept_promotion(100 + TIMEOUT, TIMEOUT).unwrap();
+        assert!(entry.status == WaitlistStatus::Promoted);
+    }
+
+    #[test]
+    fn accept_after_timeout_is_refused() {
+        let mut entry = entry(WaitlistStatus::Waiting);
+        entry.promote(100).unwrap();
+        assert!(entry.accept_promotion(101 + TIMEOUT, TIMEOUT).is_err());
+        assert!(entry.status == WaitlistStatus::PendingPromotion);
+    }
+
+    #[test]
+    fn expire_only_after_timeout() {
+        let mut entry = entry(WaitlistStatus::Waiting);
+        entry.promote(100).unwrap();
+        assert!(entry.expire(100 + TIMEOUT, TIMEOUT).is_err());
+
+        entry.expire(101 + TIMEOUT, TIMEOUT).unwrap();
+        assert!(entry.status == WaitlistStatus::Expired);
+    }
+
+    #[test]
+    fn decline_requires_pending_offer() {
+        let mut entry = entry(WaitlistStatus::Waiting);
+        assert!(entry.decline_promotion(100).is_err());
+
+        entry.promote(100).unwrap();
+        entry.decline_promotion(200).unwrap();
+        assert!(entry.status == WaitlistStatus::Declined);
+        assert_eq!(entry.updated_at, 200);
+    }
+
+    #[test]
+    fn terminal_states_refuse_every_transition() {
+        for status in [
+            WaitlistStatus::Promoted,
+            WaitlistStatus::Declined,
+            WaitlistStatus::Expired,
+            WaitlistStatus::Withdrawn,
+        ] {
+            let mut entry = entry(status);
+            assert!(entry.promote(0).is_err());
+            assert!(entry.accept_promotion(0, TIMEOUT).is_err());
+            assert!(entry.decline_promotion(0).is_err());
+            assert!(entry.expire(i64::MAX - TIMEOUT, TIMEOUT).is_err());
+            assert!(entry.withdraw(0).is_err());
+            assert!(entry.status == status);
+        }
+    }
+
+    #[test]
+    fn withdraw_only_while_waiting() {
+        let mut pending = entry(WaitlistStatus::Waiting);
+        pending.promote(100).unwrap();
+        assert!(pending.withdraw(200).is_err());
+
+        let mut waiting = entry(WaitlistStatus::Waiting);
+        waiting.withdraw(200).unwrap();
+        assert!(waiting.status == WaitlistStatus::Withdrawn);
+    }
+
+    #[test]
+    fn next_entry_follows_tier_then_join_time() {
+        let mut queue = WaitlistQueue { formation: Pubkey::new_unique(), slots: Vec::new() };
+        let general = slot(WaitlistTier::General, 10);
+        let returning = slot(WaitlistTier::ReturningStudent, 30);
+        let funded_late = slot(WaitlistTier::EmployerFunded, 40);
+        let funded_early = slot(WaitlistTier::EmployerFunded, 20);
+        for slot in [general, returning, funded_late, funded_early] {
+            queue.insert(slot).unwrap();
+        }
+
+        for expected in [funded_early, funded_late, returning, general] {
+            let head = queue.head().unwrap();
+            assert!(head == expected.student);
+            queue.remove(&head).unwrap();
+        }
+        assert!(queue.head().is_none());
+    }
+
+    fn formation(max_students: u8) -> Formation {
+        Formation {
+            trainer: Pubkey::new_unique(),
+            title: String::new(),
+            description: String::new(),
+            formation_type: FormationType::Hybrid,
+            max_students,
+            waitlist_size: 0,
+            current_students: 0,
+            current_waitlisted: 0,
+            pending_promotions: 0,
+            promotion_timeout: TIMEOUT,
+            admission_mode: AdmissionMode::FirstComeFirstServed,
+            late_threshold: 0,
+            attendance_mode: AttendanceMode::SelfCheckIn,
+            attendance_storage: AttendanceStorage::Accounts,
+            attendance_recorded: false,
+            occupied_positions: [0u8; ROSTER_BITMAP_LEN],
+            position_generations: [0u32; ROSTER_SIZE],
+            session_count: 0,
+            onsite_capacity: max_students,
+            remote_capacity: max_students,
+            current_onsite: 0,
+            current_remote: 0,
+            venue_bookings: [VenueBooking::default(); MAX_VENUE_BOOKINGS],
+            status: FormationStatus::Active,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn enrollment(formation: &mut Formation) -> Enrollment {
+        let (position, position_generation) = formation.assign_position().unwrap();
+        Enrollment {
+            formation: Pubkey::new_unique(),
+            student: Pubkey::new_unique(),
+            status: EnrollmentStatus::Enrolled,
+            position,
+            position_generation,
+            modality: Modality::OnSite,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn positions_are_reused_after_drop() {
+        let mut formation = formation(2);
+        let first = enrollment(&mut formation);
+        // Désistements répétés : aucune limite au-delà de `ROSTER_SIZE` attributions
+        for _ in 0..3 * ROSTER_SIZE {
+            let cycled = enrollment(&mut formation);
+            formation.free_position(cycled.position);
+        }
+        let last = enrollment(&mut formation);
+        assert!(last.position != first.position);
+        assert!(formation.is_position_occupied(first.position) && formation.is_position_occupied(last.position));
+    }
+
+    #[test]
+    fn position_goes_to_least_reused_free_slot() {
+        let mut formation = formation(3);
+        let first = enrollment(&mut formation);
+        let second = enrollment(&mut formation);
+        formation.free_position(first.position);
+
+        // Une position jamais attribuée passe avant celle libérée
+        let third = enrollment(&mut formation);
+        assert!(third.position != first.position && third.position != second.position);
+        assert_eq!(third.position_generation, 1);
+    }
+
+    #[test]
+    fn dropped_student_record_does_not_cover_new_occupant() {
+        let mut formation = formation(1);
+        let mut session = Session::new(Pubkey::new_unique(), Pubkey::new_unique(), String::new(), String::new(), 0, 3_600, 0);
+        // Avec une seule place, le même emplacement finit par être réattribué
+        let dropped = enrollment(&mut formation);
+        session.add_attendance(&dropped).unwrap();
+        assert!(session.covers_enrolled(&formation));
+        formation.free_position(dropped.position);
+        for _ in 1..ROSTER_SIZE {
+            let cycled = enrollment(&mut formation);
+            formation.free_position(cycled.position);
+        }
+
+        let occupant = enrollment(&mut formation);
+        assert_eq!(occupant.position, dropped.position);
+        assert!(!session.is_recorded(&occupant));
+        assert!(!session.covers_enrolled(&formation));
+
+        session.add_attendance(&occupant).unwrap();
+        assert!(session.covers_enrolled(&formation));
+        assert!(!session.is_recorded(&dropped));
+    }
+
+    fn venue_booking(capacity: u8, end_time: i64) -> VenueBooking {
+        VenueBooking {
+            session: Pubkey::new_unique(),
+            room: Pubkey::new_unique(),
+            capacity,
+            end_time,
+        }
+    }
+
+    #[test]
+    fn venue_capacity_follows_rooms_still_booked() {
+        let mut formation = formation(30);
+        assert_eq!(formation.venue_capacity(0), u8::MAX);
+
+        let small = venue_booking(10, 1_000);
+        let large = venue_booking(25, 2_000);
+        formation.add_venue_booking(small, 0).unwrap();
+        formation.add_venue_booking(large, 0).unwrap();
+        assert_eq!(formation.venue_capacity(0), 10);
+
+        // Libérer la petite salle relève le plafond
+        formation.remove_venue_booking(&small.session);
+        assert_eq!(formation.venue_capacity(0), 25);
+
+        // Agrandissement de la salle répercuté sur la formation
+        assert!(formation.update_venue_capacity(&large.room, 28, 0));
+        assert_eq!(formation.venue_capacity(0), 28);
+
+        // Une session terminée ne plafonne plus rien
+        assert_eq!(formation.venue_capacity(2_001), u8::MAX);
+    }
+
+    #[test]
+    fn ended_venue_bookings_free_their_slot() {
+        let mut formation = formation(30);
+        for _ in 0..MAX_VENUE_BOOKINGS {
+            formation.add_venue_booking(venue_booking(20, 1_000), 0).unwrap();
+        }
+        assert!(formation.add_venue_booking(venue_booking(20, 3_000), 0).is_err());
+        formation.add_venue_booking(venue_booking(15, 3_000), 1_001).unwrap();
+        assert_eq!(formation.venue_capacity(1_001), 15);
+    }
+
+    #[test]
+    fn each_segment_keeps_its_own_signer() {
+        let mut session = Session::new(Pubkey::new_unique(), Pubkey::new_unique(), String::new(), String::new(), 0, 3_600, 0);
+        session.segments = vec![
+            SessionSegment { name: String::new(), start_time: 0, end_time: 1_000 },
+            SessionSegment { name: String::new(), start_time: 2_000, end_time: 3_600 },
+        ];
+        let mut trainer_attendance = TrainerAttendance {
+            session: Pubkey::new_unique(),
+            trainer: Pubkey::default(),
+            signed_at: 0,
+            segment_signed_at: [0; MAX_SESSION_SEGMENTS],
+            segment_signers: [Pubkey::default(); MAX_SESSION_SEGMENTS],
+            created_at: 0,
+            updated_at: 0,
+        };
+        let (titular, substitute) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        trainer_attendance.sign(&session, titular, 500).unwrap();
+        assert!(!trainer_attendance.is_complete(&session));
+        // Le remplaçant signe l'après-midi sans effacer la signature du matin
+        trainer_attendance.sign(&session, substitute, 2_500).unwrap();
+        assert!(trainer_attendance.is_complete(&session));
+        assert_eq!(trainer_attendance.trainer, titular);
+        assert_eq!(&trainer_attendance.segment_signers[..2], &[titular, substitute]);
+    }
+
+    #[test]
+    fn attendance_status_at_window_bounds() {
+        let late_threshold = 600;
+        let session = Session::new(Pubkey::new_unique(), Pubkey::new_unique(), String::new(), String::new(), 10_000, 13_600, 0);
+        let opens_at = session.check_in_opens_at();
+        let closes_at = session.check_in_closes_at();
+        assert_eq!(opens_at, 10_000 - CHECK_IN_OPEN_MARGIN);
+        assert_eq!(closes_at, 13_600 + SESSION_TIMEOUT);
+
+        let err = session.attendance_status_at(opens_at - 1, late_threshold).err().unwrap();
+        assert_eq!(err, AlyraError::CheckInNotOpen.into());
+        assert!(session.attendance_status_at(opens_at, late_threshold).unwrap() == AttendanceStatus::Present);
+        // Le seuil de retard est inclusif
+        assert!(session.attendance_status_at(10_000 + late_threshold, late_threshold).unwrap() == AttendanceStatus::Present);
+        assert!(session.attendance_status_at(10_000 + late_threshold + 1, late_threshold).unwrap() == AttendanceStatus::Late);
+        assert!(session.attendance_status_at(closes_at, late_threshold).unwrap() == AttendanceStatus::Late);
+        let err = session.attendance_status_at(closes_at + 1, late_threshold).err().unwrap();
+        assert_eq!(err, AlyraError::CheckInClosed.into());
+    }
+
+    // Chaîne de codes : le maillon de la fenêtre `i` est l'antécédent de celui de `i - 1`
+    fn code_chain(length: usize) -> Vec<[u8; 32]> {
+        let mut links = vec![[7u8; 32]];
+        for _ in 1..length {
+            let previous = hash(links.last().unwrap()).to_bytes();
+            links.push(previous);
+        }
+        links.reverse();
+        links
+    }
+
+    #[test]
+    fn chain_link_respects_window_bounds_and_step_limit() {
+        let length = MAX_CODE_CHAIN_STEPS as usize + 60;
+        let links = code_chain(length);
+        let mut session = Session::new(Pubkey::new_unique(), Pubkey::new_unique(), String::new(), String::new(), 0, 3_600, 0);
+        session.code_anchor = links[0];
+        session.code_anchor_window = 0;
+        session.code_chain_length = length as u32;
+
+        let gap = MAX_CODE_CHAIN_STEPS as usize;
+        let err = session.accept_chain_link(links[gap + 1], gap as i64 + 1).unwrap_err();
+        assert_eq!(err, AlyraError::CodeChainGapTooLarge.into());
+        session.accept_chain_link(links[gap], gap as i64).unwrap();
+        assert_eq!(session.code_anchor_window, gap as i64);
+
+        // Une fenêtre antérieure à l'ancre ou un maillon d'une autre fenêtre est refusé
+        let err = session.accept_chain_link(links[gap - 1], gap as i64 - 1).unwrap_err();
+        assert_eq!(err, AlyraError::InvalidCheckInCode.into());
+        let err = session.accept_chain_link(links[gap + 2], gap as i64 + 3).unwrap_err();
+        assert_eq!(err, AlyraError::InvalidCheckInCode.into());
+
+        // Dernière fenêtre de la chaîne acceptée, aucune au-delà
+        session.accept_chain_link(links[length - 1], length as i64 - 1).unwrap();
+        let err = session.accept_chain_link(hash(&links[length - 1]).to_bytes(), length as i64).unwrap_err();
+        assert_eq!(err, AlyraError::InvalidCheckInCode.into());
+    }
+
+    #[test]
+    fn accumulator_leaves_tally_session_totals() {
+        let mut formation = formation(2);
+        let mut session = Session::new(Pubkey::new_unique(), Pubkey::new_unique(), String::new(), String::new(), 0, 3_600, 0);
+        let present = enrollment(&mut formation);
+        let absent = enrollment(&mut formation);
+
+        session.add_leaf(&present, AttendanceStatus::Late, &[1; 32]).unwrap();
+        assert!(!session.covers_enrolled(&formation));
+        session.add_leaf(&absent, AttendanceStatus::Absent, &[2; 32]).unwrap();
+        assert!(session.covers_enrolled(&formation));
+
+        assert_eq!((session.late_count, session.absent_count, session.present_count), (1, 1, 0));
+        assert_eq!(session.attendance_rate(), 5_000);
+        // Empreinte chaînée dans l'ordre des feuilles
+        let first = hashv(&[&[0u8; 32], &[1u8; 32]]).to_bytes();
+        assert_eq!(session.attendance_hash, hashv(&[&first, &[2u8; 32]]).to_bytes());
+    }
+
+    fn schedule_entry(start_time: i64, end_time: i64) -> ScheduleEntry {
+        ScheduleEntry {
+            session: Pubkey::new_unique(),
+            formation: Pubkey::new_unique(),
+            start_time,
+            end_time,
+        }
+    }
+
+    fn starts(schedule: &TrainerSchedule) -> Vec<i64> {
+        schedule.entries.iter().map(|entry| entry.start_time).collect()
+    }
+
+    #[test]
+    fn schedule_refuses_overlap_but_allows_back_to_back() {
+        let mut schedule = TrainerSchedule { trainer: Pubkey::new_unique(), entries: Vec::new() };
+        schedule.insert(schedule_entry(1_000, 2_000), 0).unwrap();
+
+        for (start, end) in [(1_500, 2_500), (500, 1_001), (1_200, 1_800), (0, 3_000)] {
+            let err = schedule.insert(schedule_entry(start, end), 0).unwrap_err();
+            assert_eq!(err, AlyraError::ScheduleConflict.into());
+        }
+
+        // Une session peut commencer à l'instant où la précédente se termine
+        schedule.insert(schedule_entry(2_000, 3_000), 0).unwrap();
+        schedule.insert(schedule_entry(0, 1_000), 0).unwrap();
+        assert_eq!(starts(&schedule), vec![0, 1_000, 2_000]);
+    }
+
+    #[test]
+    fn schedule_purges_finished_sessions_before_inserting() {
+        let mut schedule = TrainerSchedule { trainer: Pubkey::new_unique(), entries: Vec::new() };
+        schedule.insert(schedule_entry(1_000, 2_000), 0).unwrap();
+        schedule.insert(schedule_entry(3_000, 4_000), 0).unwrap();
+
+        // Terminée à `now` : conservée ; terminée avant : purgée
+        schedule.insert(schedule_entry(5_000, 6_000), 2_000).unwrap();
+        assert_eq!(starts(&schedule), vec![1_000, 3_000, 5_000]);
+        schedule.insert(schedule_entry(1_500, 2_500), 2_001).unwrap();
+        assert_eq!(starts(&schedule), vec![1_500, 3_000, 5_000]);
+    }
+
+    #[test]
+    fn schedule_full_only_counts_sessions_not_yet_finished() {
+        let mut entries = Vec::new();
+        for i in 0..2 {
+            schedule_entry(i * 1_000, i * 1_000 + 500)
+                .insert_into(&mut entries, 2, 0, AlyraError::ScheduleConflict, AlyraError::ScheduleFull)
+                .unwrap();
+        }
+        let err = schedule_entry(5_000, 6_000)
+            .insert_into(&mut entries, 2, 0, AlyraError::ScheduleConflict, AlyraError::ScheduleFull)
+            .unwrap_err();
+        assert_eq!(err, AlyraError::ScheduleFull.into());
+
+        schedule_entry(5_000, 6_000)
+            .insert_into(&mut entries, 2, 501, AlyraError::ScheduleConflict, AlyraError::ScheduleFull)
+            .unwrap();
+        assert_eq!(entries.len(), 2);
+    }
+
+    fn series(rule: RecurrenceRule, excluded_dates: Vec<i64>) -> SessionSeries {
+        SessionSeries {
+            formation: Pubkey::new_unique(),
+            trainer: Pubkey::new_unique(),
+            title: String::new(),
+            description: String::new(),
+            first_start_time: 9 * 3_600,
+            duration: 3_600,
+            rule,
+            excluded_dates,
+            count: 3,
+            created: 0,
+            next_occurrence: 0,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn series_skips_excluded_days_whatever_the_time_of_day() {
+        // Premier et dernier instant de deux jours consécutifs exclus
+        let mut series = series(RecurrenceRule::Daily, vec![SECONDS_PER_DAY, 3 * SECONDS_PER_DAY - 1]);
+        let nine = 9 * 3_600;
+        assert_eq!(series.next_start_time().unwrap(), nine);
+        assert_eq!(series.next_start_time().unwrap(), 3 * SECONDS_PER_DAY + nine);
+        assert_eq!(series.next_start_time().unwrap(), 4 * SECONDS_PER_DAY + nine);
+        assert_eq!(series.next_occurrence, 5);
+    }
+
+    #[test]
+    fn weekly_series_only_skips_excluded_occurrence_days() {
+        // Le jour 3 ne porte aucune occurrence, le jour 7 écarte la deuxième semaine
+        let mut series = series(RecurrenceRule::Weekly, vec![3 * SECONDS_PER_DAY, 7 * SECONDS_PER_DAY + 1]);
+        assert_eq!(series.next_start_time().unwrap(), 9 * 3_600);
+        assert_eq!(series.next_start_time().unwrap(), 14 * SECONDS_PER_DAY + 9 * 3_600);
+    }
+
+    fn attendance_leaf(index: u8) -> AttendanceLeaf {
+        AttendanceLeaf {
+            formation: Pubkey::new_from_array([1; 32]),
+            session: Pubkey::new_from_array([2; 32]),
+            student: Pubkey::new_from_array([index; 32]),
+            status: AttendanceStatus::Present,
+            modality: Modality::OnSite,
+            check_in_time: index as i64,
+        }
+    }
+
+    // Preuve calculée sur l'arbre complet, les positions vides valant l'arbre nul
+    fn merkle_proof(leaves: &[[u8; 32]], index: usize) -> Vec<[u8; 32]> {
+        let mut level = leaves.to_vec();
+        let mut zero = [0u8; 32];
+        let mut index = index;
+        let mut proof = Vec::with_capacity(MERKLE_DEPTH);
+        for _ in 0..MERKLE_DEPTH {
+            proof.push(level.get(index ^ 1).copied().unwrap_or(zero));
+            level = level
+                .chunks(2)
+                .map(|pair| merkle::hash_node(&pair[0], pair.get(1).unwrap_or(&zero)))
+                .collect();
+            zero = merkle::hash_node(&zero, &zero);
+            index /= 2;
+        }
+        proof
+    }
+
+    #[test]
+    fn accumulator_root_matches_every_proof() {
+        let mut accumulator = AttendanceAccumulator {
+            formation: Pubkey::new_from_array([1; 32]),
+            root: merkle::empty_root(),
+            leaf_count: 0,
+            frontier: [[0u8; 32]; MERKLE_DEPTH],
+        };
+        let mut hashes = Vec::new();
+
+        // Jusqu'à 17 feuilles : arbres remplis sur 1 à 5 niveaux, pleins ou non
+        for i in 0..17u8 {
+            let leaf = attendance_leaf(i);
+            hashes.push(leaf.hash().unwrap());
+            accumulator.append(*hashes.last().unwrap()).unwrap();
+            assert_eq!(accumulator.leaf_count, hashes.len() as u64);
+
+            for j in 0..hashes.len() {
+                let proof = merkle_proof(&hashes, j);
+                assert!(merkle::verify_proof(&accumulator.root, &attendance_leaf(j as u8), j as u64, &proof));
+            }
+        }
+    }
+
+    #[test]
+    fn proof_rejects_wrong_leaf_or_index() {
+        let mut accumulator = AttendanceAccumulator {
+            formation: Pubkey::new_from_array([1; 32]),
+            root: merkle::empty_root(),
+            leaf_count: 0,
+            frontier: [[0u8; 32]; MERKLE_DEPTH],
+        };
+        let hashes: Vec<[u8; 32]> = (0..5).map(|i| attendance_leaf(i).hash().unwrap()).collect();
+        for hash in &hashes {
+            accumulator.append(*hash).unwrap();
+        }
+
+        let proof = merkle_proof(&hashes, 3);
+        assert!(merkle::verify_proof(&accumulator.root, &attendance_leaf(3), 3, &proof));
+        assert!(!merkle::verify_proof(&accumulator.root, &attendance_leaf(4), 3, &proof));
+        assert!(!merkle::verify_proof(&accumulator.root, &attendance_leaf(3), 2, &proof));
+        assert!(!merkle::verify_proof(&accumulator.root, &attendance_leaf(3), 3, &proof[1..]));
+    }
+
+    #[test]
+    fn empty_accumulator_root_is_the_zero_tree() {
+        let zero_proof = merkle_proof(&[], 0);
+        let mut node = [0u8; 32];
+        for sibling in &zero_proof {
+            node = merkle::hash_node(&node, sibling);
+        }
+        assert_eq!(node, merkle::empty_root());
+    }
+}
@@ -13,11 +13,135 @@ pub enum FormationType {
     Hybrid,
 }
 
+// Mode d'admission : premier arrivé, premier servi ou tirage au sort
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AdmissionMode {
+    #[default]
+    FirstComeFirstServed,
+    Lottery,
+}
+
+// Qui enregistre les présences d'une formation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AttendanceMode {
+    // Chaque étudiant pointe avec son wallet
+    #[default]
+    SelfCheckIn,
+    // Le formateur fait l'appel
+    RollCall,
+    Both,
+}
+
+// Modalité de suivi d'un étudiant : sur place ou à distance
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Modality {
+    #[default]
+    OnSite,
+    Remote,
+}
+
+// Stockage des présences : un compte par étudiant, ou un registre zero-copy par session
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AttendanceStorage {
+    #[default]
+    Accounts,
+    Roster,
+    // Feuilles d'un accumulateur de Merkle par formation : aucun compte par présence,
+    // mais la promotion reste bornée à `MAX_FORMATION_CAPACITY` inscrits, chaque
+    // session suivant ses présences par position
+    Accumulator,
+}
+
+// Preuve de présence exigée au pointage
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CheckInMethod {
+    // Code signé par le formateur, vérifié via le programme Ed25519
+    #[default]
+    TrainerSignature,
+    // Préimages successives d'une chaîne de hachage engagée à la création de la session
+    HashChain,
+}
+
+// Engagement sur une chaîne de hachage : `tail = sha256^length(graine)`.
+// Le code de la fenêtre k est `sha256^(length - 1 - k)(graine)`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct HashChainCommitment {
+    pub tail: [u8; 32],
+    pub length: u32,
+}
+
+// Récurrence d'une série de sessions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RecurrenceRule {
+    Daily,
+    Weekly,
+    EveryNDays(u16),
+}
+
+// Paramètres de récurrence passés à `create_session_series`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    pub rule: RecurrenceRule,
+    // Jours sans session (jours fériés, congés...)
+    pub excluded_dates: Vec<i64>,
+    pub count: u16,
+}
+
+// Plage d'émargement d'une session (matin, après-midi...)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct SessionSegment {
+    pub name: String,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+// Règle déterminant si une session découpée en plages compte comme suivie
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SegmentRule {
+    #[default]
+    AllSegments,
+    AnySegment,
+    AtLeast(u8),
+}
+
+// Cycle de la feuille d'émargement : la clôture peut s'étaler sur plusieurs transactions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SessionStatus {
+    #[default]
+    Scheduled,
+    Finalizing,
+    Finalized,
+    // Annulée : plus aucune présence ne peut être enregistrée
+    Cancelled,
+}
+
+// Un départ anticipé n'est pas un statut : il est porté par `Attendance.left_early`
+// au pointage de sortie et compté dans `left_early_count`
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
 pub enum AttendanceStatus {
     Present,
     Absent,
     Late,
+    // Absence couverte par un justificatif accepté par le formateur
+    Excused,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DisputeStatus {
+    #[default]
+    None,
+    Open,
+    Accepted,
+    Rejected,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JustificationStatus {
+    #[default]
+    None,
+    Pending,
+    Accepted,
+    Rejected,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
@@ -34,6 +158,16 @@ pub enum WaitlistStatus {
     Promoted,
     Declined,
     Expired,
+    Withdrawn,
+}
+
+// Classes de priorité de la liste d'attente, de la plus prioritaire à la moins prioritaire
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum WaitlistTier {
+    EmployerFunded,
+    ReturningStudent,
+    #[default]
+    General,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
@@ -71,6 +205,18 @@ impl Space for FormationType {
     const INIT_SPACE: usize = 1;
 }
 
+impl Space for AdmissionMode {
+    const INIT_SPACE: usize = 1;
+}
+
+impl Space for AttendanceMode {
+    const INIT_SPACE: usize = 1;
+}
+
+impl Space for CheckInMethod {
+    const INIT_SPACE: usize = 1;
+}
+
 impl Space for AttendanceStatus {
     const INIT_SPACE: usize = 1;
 }
@@ -83,6 +229,10 @@ impl Space for WaitlistStatus {
     const INIT_SPACE: usize = 1;
 }
 
+impl Space for WaitlistTier {
+    const INIT_SPACE: usize = 1;
+}
+
 impl Space for EnrollmentStatus {
     const INIT_SPACE: usize = 1;
 }
@@ -91,6 +241,34 @@ impl Space for FormationStatus {
     const INIT_SPACE: usize = 1;
 }
 
+impl Space for SegmentRule {
+    const INIT_SPACE: usize = 2;
+}
+
+impl Space for SessionStatus {
+    const INIT_SPACE: usize = 1;
+}
+
+impl Space for DisputeStatus {
+    const INIT_SPACE: usize = 1;
+}
+
+impl Space for JustificationStatus {
+    const INIT_SPACE: usize = 1;
+}
+
+impl Space for AttendanceStorage {
+    const INIT_SPACE: usize = 1;
+}
+
+impl Space for RecurrenceRule {
+    const INIT_SPACE: usize = 3;
+}
+
+impl Space for Modality {
+    const INIT_SPACE: usize = 1;
+}
+
 #[account]
 pub struct AccessRequest {
     pub user: Pubkey,
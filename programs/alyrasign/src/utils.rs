@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount, Transfer};
+
+// Crée un compte PDA appartenant au programme, pour les instructions qui
+// initialisent un nombre variable de comptes passés en `remaining_accounts`.
+// `signer_seeds` contient les seeds du nouveau compte, et celles du payeur si
+// celui-ci est lui-même une PDA.
+pub fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    new_account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let current_lamports = new_account.lamports();
+
+    if current_lamports == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                CreateAccount { from: payer.clone(), to: new_account.clone() },
+                signer_seeds,
+            ),
+            rent,
+            space as u64,
+            &crate::ID,
+        );
+    }
+
+    // Compte déjà crédité : compléter le loyer puis allouer et assigner
+    let shortfall = rent.saturating_sub(current_lamports);
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                Transfer { from: payer.clone(), to: new_account.clone() },
+                signer_seeds,
+            ),
+            shortfall,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Allocate { account_to_allocate: new_account.clone() },
+            signer_seeds,
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Assign { account_to_assign: new_account.clone() },
+            signer_seeds,
+        ),
+        &crate::ID,
+    )
+}
+
+pub fn read_account<T: AccountDeserialize + Owner>(info: &AccountInfo) -> Result<T> {
+    require!(info.owner == &T::owner(), ErrorCode::AccountOwnedByWrongProgram);
+    let data = info.try_borrow_data()?;
+    T::try_deserialize(&mut &data[..])
+}
+
+pub fn write_account<T: AccountSerialize>(info: &AccountInfo, account: &T) -> Result<()> {
+    require!(info.is_writable, ErrorCode::AccountNotMutable);
+    let mut data = info.try_borrow_mut_data()?;
+    account.try_serialize(&mut &mut data[..])
+}